@@ -0,0 +1,106 @@
+/*
+    PTB データセットで LSTM の言語モデルを学習し、パープレキシティを表示する
+    CELL を変えると LSTM の代わりに GRU や単純な RNN を使う
+    学習後、いくつかのサンプリング方法で文章を生成する
+*/
+
+use std::{fs::File, io::BufReader};
+
+use ch02::{
+    corpus::{
+        bptt_dataset::{BpttDataset, InitParamsOfBpttDataset},
+        reader::{InitParamsOfReader, END_OF_SENTENCE},
+        tokenizer::DefaultTokenizer,
+        Corpus,
+    },
+    util::generate_text::{generate_text, InitParamsOfTextGeneration},
+};
+use ndarray::{Array1, Array2, Array3};
+use neural_network::{
+    network::{
+        generation::Sampling,
+        rnn_lm::{RecurrentCellKind, RnnLm},
+    },
+    optimizer::{
+        imp::sgd::{learning_rate::LearningRate, SGD},
+        optimizer::Optimizer,
+    },
+    rnn_lm_trainer::RnnLmTrainer,
+};
+
+const FILE_PATH: &str = "examples/ptb.train.txt";
+const BATCH_SIZE: usize = 20;
+const TIME_SIZE: usize = 35;
+const WORDVEC_SIZE: usize = 100;
+const HIDDEN_SIZE: usize = 100;
+const CELL: RecurrentCellKind = RecurrentCellKind::Lstm;
+const LEARNING_RATE: f32 = 20.;
+const MAX_EPOCH: usize = 4;
+const MAX_GRAD: f32 = 0.25;
+const EVAL_INTERVAL: usize = 20;
+const TEST_RATIO: f32 = 0.05;
+const START_WORD: &str = "you";
+const MAX_LEN: usize = 30;
+
+fn main() {
+    let file = BufReader::new(File::open(FILE_PATH).unwrap());
+    let corpus = Corpus::from_reader(
+        file,
+        &DefaultTokenizer,
+        InitParamsOfReader {
+            end_of_sentence: Some(END_OF_SENTENCE.to_string()),
+        },
+    )
+    .unwrap();
+    println!("vocab size: {}", corpus.vocab_size());
+
+    let mut dataset = BpttDataset::new(
+        &corpus,
+        InitParamsOfBpttDataset {
+            batch_size: BATCH_SIZE,
+            time_size: TIME_SIZE,
+            test_ratio: TEST_RATIO,
+        },
+    );
+
+    let network: RnnLm<Array3<f32>, Array2<f32>, Array1<f32>> =
+        RnnLm::with_cell(corpus.vocab_size(), WORDVEC_SIZE, HIDDEN_SIZE, CELL);
+    let optimizer = SGD::new(LearningRate::new(LEARNING_RATE));
+    let mut trainer = RnnLmTrainer::new(network, optimizer);
+
+    trainer.fit(&mut dataset, MAX_EPOCH, Some(MAX_GRAD), EVAL_INTERVAL);
+    trainer.plot_perplexity("rnnlm_perplexity.png").unwrap();
+    trainer
+        .plot_test_perplexity("rnnlm_test_perplexity.png")
+        .unwrap();
+
+    let mut network = trainer.into_network();
+    let mut rng = rand::thread_rng();
+    let samplings = [
+        Sampling::Greedy,
+        Sampling::Temperature(1.),
+        Sampling::TopK {
+            k: 10,
+            temperature: 1.,
+        },
+        Sampling::TopP {
+            p: 0.9,
+            temperature: 0.8,
+        },
+    ];
+    for sampling in samplings {
+        let text = generate_text(
+            &mut network,
+            &corpus,
+            START_WORD,
+            InitParamsOfTextGeneration {
+                max_len: MAX_LEN,
+                stop_words: vec![END_OF_SENTENCE.to_string()],
+                sampling,
+            },
+            &mut rng,
+        )
+        .unwrap();
+        println!("{:?}: {}", sampling, text);
+    }
+}
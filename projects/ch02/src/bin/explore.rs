@@ -0,0 +1,461 @@
+/*
+    単語ベクトルを対話的に調べる REPL
+
+    使い方:
+    cargo run --bin explore -- <コーパスのファイル> [--window <N>] [--dim <N>]
+    cargo run --bin explore -- --load <word2vec 形式のファイル> [--binary]
+
+    コーパスから作る場合は共起行列 (co)・PPMI (ppmi)・SVD で次元削減した PPMI (svd) を
+    切り替えて使える（各行列は初めて使うときに作る）
+    各行列はノルムだけを計算した SimilarityIndex に入れて持つので、疎行列は疎行列のまま問い合わせる
+*/
+
+use std::{
+    env,
+    fs::File,
+    io::{self, BufRead, BufReader, Write},
+    process,
+};
+
+use ch02::{
+    corpus::{reader::InitParamsOfReader, tokenizer::DefaultTokenizer, Corpus},
+    similarity_index::SimilarityIndex,
+    util::{
+        analogy::{analogy, AnalogyMethod},
+        cos_similarity::cos_similarity,
+        most_similar::{most_similar_in, SimilarityMetric},
+        query_error::QueryError,
+    },
+    word2vec_format::{load_word2vec, Word2VecFormat},
+    word_matrix::{
+        csr_matrix::CsrMatrix,
+        sparse_co_matrix::SparseCoMatrix,
+        svd::{InitParamsOfRandomizedSvd, SvdMatrix},
+        WordMatrix,
+    },
+};
+use ndarray::Array2;
+
+const DEFAULT_WINDOW_SIZE: usize = 2;
+const DEFAULT_DIM: usize = 100;
+const DEFAULT_TOP: usize = 5;
+
+const HELP: &str = "\
+commands:
+  <word>                 most similar words (same as `sim <word>`)
+  sim <word>             most similar words
+  analogy <a> <b> <c>    a : b = c : ?
+  pair <word1> <word2>   cosine similarity of two words
+  matrix co|ppmi|svd     switch word matrix (only when built from a corpus)
+  method add|mul         switch analogy method (3CosAdd / 3CosMul)
+  metric cos|dot|euclid  switch similarity metric
+  top <N>                number of results
+  help                   show this message
+  quit                   exit";
+
+#[derive(Clone, Copy, PartialEq)]
+enum MatrixKind {
+    Co,
+    Ppmi,
+    Svd,
+    Loaded,
+}
+
+struct Explorer {
+    corpus: Corpus,
+    window_size: usize,
+    dim: usize,
+    co_matrix: Option<SimilarityIndex<SparseCoMatrix>>,
+    ppmi: Option<SimilarityIndex<CsrMatrix>>,
+    svd: Option<SimilarityIndex<SvdMatrix>>,
+    loaded: Option<SimilarityIndex<Array2<f32>>>,
+    kind: MatrixKind,
+    top: usize,
+    metric: SimilarityMetric,
+    method: AnalogyMethod,
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let mut explorer = match parse_args(&args).and_then(Explorer::new) {
+        Ok(explorer) => explorer,
+        Err(message) => {
+            eprintln!("{}", message);
+            eprintln!("usage: explore <corpus file> [--window <N>] [--dim <N>]");
+            eprintln!("       explore --load <word2vec file> [--binary]");
+            process::exit(1);
+        }
+    };
+    println!("vocab size: {}", explorer.corpus.vocab_size());
+    println!("{}", HELP);
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("> ");
+        io::stdout().flush().unwrap();
+
+        let line = match lines.next() {
+            Some(line) => line.unwrap(),
+            None => break,
+        };
+        match parse_command(&line) {
+            Ok(Command::Empty) => {}
+            Ok(Command::Quit) => break,
+            Ok(Command::Help) => println!("{}", HELP),
+            Ok(Command::Top(top)) => explorer.top = top,
+            Ok(Command::Metric(metric)) => explorer.metric = metric,
+            Ok(Command::Method(method)) => explorer.method = method,
+            Ok(Command::Matrix(kind)) => explorer.switch_matrix(kind),
+            Ok(Command::Query(query)) => explorer.run(query),
+            Err(message) => println!("{}", message),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Args {
+    source: Source,
+    window_size: usize,
+    dim: usize,
+}
+
+#[derive(Debug, PartialEq)]
+enum Source {
+    Corpus(String),
+    Word2Vec { path: String, binary: bool },
+}
+
+fn parse_args(args: &[String]) -> Result<Args, String> {
+    let mut corpus_path = None;
+    let mut load_path = None;
+    let mut binary = false;
+    let mut window_size = DEFAULT_WINDOW_SIZE;
+    let mut dim = DEFAULT_DIM;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--load" => {
+                let path = args.next().ok_or("--load requires a value")?;
+                load_path = Some(path.clone());
+            }
+            "--binary" => binary = true,
+            "--window" => window_size = parse_option(args.next(), "--window")?,
+            "--dim" => dim = parse_option(args.next(), "--dim")?,
+            option if option.starts_with("--") => {
+                return Err(format!("unknown option: {}", option))
+            }
+            path if corpus_path.is_none() => corpus_path = Some(path.to_string()),
+            path => return Err(format!("unexpected argument: {}", path)),
+        }
+    }
+
+    let source = match (corpus_path, load_path) {
+        (Some(path), None) => Source::Corpus(path),
+        (None, Some(path)) => Source::Word2Vec { path, binary },
+        _ => return Err("specify either a corpus file or --load".to_string()),
+    };
+
+    Ok(Args {
+        source,
+        window_size,
+        dim,
+    })
+}
+
+fn parse_option(value: Option<&String>, name: &str) -> Result<usize, String> {
+    value
+        .ok_or_else(|| format!("{} requires a value", name))?
+        .parse()
+        .map_err(|e| format!("{}: {}", name, e))
+}
+
+enum Command<'a> {
+    Empty,
+    Quit,
+    Help,
+    Top(usize),
+    Metric(SimilarityMetric),
+    Method(AnalogyMethod),
+    Matrix(MatrixKind),
+    Query(Query<'a>),
+}
+
+#[derive(Debug, PartialEq)]
+enum Query<'a> {
+    MostSimilar(&'a str),
+    Pair(&'a str, &'a str),
+    Analogy(&'a str, &'a str, &'a str),
+}
+
+fn parse_command(line: &str) -> Result<Command<'_>, String> {
+    let words = line.split_whitespace().collect::<Vec<_>>();
+    let command = match words.as_slice() {
+        [] => Command::Empty,
+        ["quit"] | ["exit"] => Command::Quit,
+        ["help"] => Command::Help,
+        ["top", n] => Command::Top(n.parse().map_err(|e| format!("{}", e))?),
+        ["metric", metric] => Command::Metric(match *metric {
+            "cos" => SimilarityMetric::Cosine,
+            "dot" => SimilarityMetric::Dot,
+            "euclid" => SimilarityMetric::Euclidean,
+            _ => return Err(format!("unknown metric: {}", metric)),
+        }),
+        ["method", method] => Command::Method(match *method {
+            "add" => AnalogyMethod::CosAdd,
+            "mul" => AnalogyMethod::CosMul,
+            _ => return Err(format!("unknown method: {}", method)),
+        }),
+        ["matrix", kind] => Command::Matrix(match *kind {
+            "co" => MatrixKind::Co,
+            "ppmi" => MatrixKind::Ppmi,
+            "svd" => MatrixKind::Svd,
+            _ => return Err(format!("unknown matrix: {}", kind)),
+        }),
+        ["sim", word] | [word] => Command::Query(Query::MostSimilar(word)),
+        ["pair", word1, word2] => Command::Query(Query::Pair(word1, word2)),
+        ["analogy", a, b, c] => Command::Query(Query::Analogy(a, b, c)),
+        _ => return Err("unknown command (type `help`)".to_string()),
+    };
+    Ok(command)
+}
+
+impl Explorer {
+    fn new(args: Args) -> Result<Self, String> {
+        let Args {
+            source,
+            window_size,
+            dim,
+        } = args;
+
+        let (corpus, loaded) = match source {
+            Source::Corpus(path) => {
+                let file = File::open(&path).map_err(|e| format!("{}: {}", path, e))?;
+                let corpus = Corpus::from_reader(
+                    BufReader::new(file),
+                    &DefaultTokenizer,
+                    InitParamsOfReader {
+                        end_of_sentence: None,
+                    },
+                )
+                .map_err(|e| format!("{}: {}", path, e))?;
+                (corpus, None)
+            }
+            Source::Word2Vec { path, binary } => {
+                let file = File::open(&path).map_err(|e| format!("{}: {}", path, e))?;
+                let format = if binary {
+                    Word2VecFormat::Binary
+                } else {
+                    Word2VecFormat::Text
+                };
+                let (corpus, word_vectors) = load_word2vec(&mut BufReader::new(file), format)
+                    .map_err(|e| format!("{}: {}", path, e))?;
+                (corpus, Some(SimilarityIndex::new(word_vectors)))
+            }
+        };
+
+        Ok(Self {
+            corpus,
+            window_size,
+            dim,
+            co_matrix: None,
+            ppmi: None,
+            svd: None,
+            kind: if loaded.is_some() {
+                MatrixKind::Loaded
+            } else {
+                MatrixKind::Ppmi
+            },
+            loaded,
+            top: DEFAULT_TOP,
+            metric: SimilarityMetric::Cosine,
+            method: AnalogyMethod::CosAdd,
+        })
+    }
+
+    fn switch_matrix(&mut self, kind: MatrixKind) {
+        if self.kind == MatrixKind::Loaded {
+            println!("only the loaded word vectors are available");
+            return;
+        }
+        self.kind = kind;
+    }
+
+    // 必要になった行列だけを作る
+    fn prepare(&mut self) {
+        if self.kind != MatrixKind::Loaded {
+            self.prepare_matrix();
+        }
+    }
+
+    fn prepare_matrix(&mut self) {
+        if self.co_matrix.is_none() {
+            println!("building co-occurrence matrix...");
+            let co_matrix = SparseCoMatrix::new(&self.corpus, self.window_size);
+            self.co_matrix = Some(SimilarityIndex::new(co_matrix));
+        }
+        if self.kind != MatrixKind::Co && self.ppmi.is_none() {
+            println!("calculating PPMI...");
+            let co_matrix = self.co_matrix.as_ref().unwrap().word_matrix();
+            self.ppmi = Some(SimilarityIndex::new(co_matrix.ppmi(false, None)));
+        }
+        if self.kind == MatrixKind::Svd && self.svd.is_none() {
+            println!("calculating SVD...");
+            let ppmi = self.ppmi.as_ref().unwrap().word_matrix();
+            let dim = self.dim.min(ppmi.dim().0);
+            let svd = SvdMatrix::randomized(
+                ppmi,
+                InitParamsOfRandomizedSvd {
+                    dim,
+                    n_oversamples: 10,
+                    n_iter: 5,
+                },
+                &mut rand::thread_rng(),
+            );
+            self.svd = Some(SimilarityIndex::new(svd));
+        }
+    }
+
+    fn run(&mut self, query: Query) {
+        self.prepare();
+        let result = match self.kind {
+            MatrixKind::Co => self.query(&query, self.co_matrix.as_ref().unwrap()),
+            MatrixKind::Ppmi => self.query(&query, self.ppmi.as_ref().unwrap()),
+            MatrixKind::Svd => self.query(&query, self.svd.as_ref().unwrap()),
+            MatrixKind::Loaded => self.query(&query, self.loaded.as_ref().unwrap()),
+        };
+        if let Err(e) = result {
+            println!("{}", e);
+        }
+    }
+
+    fn query<T: WordMatrix>(
+        &self,
+        query: &Query,
+        index: &SimilarityIndex<T>,
+    ) -> Result<(), QueryError> {
+        let corpus = &self.corpus;
+        let word_matrix = index.word_matrix();
+        match *query {
+            Query::MostSimilar(word) => {
+                let results = most_similar_in(word, corpus, index, self.top, self.metric)?;
+                print_results(&results);
+            }
+            Query::Pair(word1, word2) => {
+                let word_id = |word: &str| {
+                    corpus
+                        .word_to_id(word)
+                        .ok_or_else(|| QueryError::UnknownWord(word.to_string()))
+                };
+                let (id1, id2) = (word_id(word1)?, word_id(word2)?);
+                let similarity = cos_similarity(
+                    word_matrix.row(id1).view(),
+                    word_matrix.row(id2).view(),
+                    None,
+                );
+                println!("{}", similarity);
+            }
+            Query::Analogy(a, b, c) => {
+                let results = analogy((a, b, c), corpus, word_matrix, self.top, self.method)?;
+                print_results(&results);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn print_results(results: &[(String, f32)]) {
+    for (word, score) in results {
+        println!("{}: {}", word, score);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &str) -> Vec<String> {
+        args.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(
+            parse_args(&args("ptb.txt --window 3")),
+            Ok(Args {
+                source: Source::Corpus("ptb.txt".to_string()),
+                window_size: 3,
+                dim: DEFAULT_DIM,
+            })
+        );
+        assert_eq!(
+            parse_args(&args("--load vectors.bin --binary")),
+            Ok(Args {
+                source: Source::Word2Vec {
+                    path: "vectors.bin".to_string(),
+                    binary: true,
+                },
+                window_size: DEFAULT_WINDOW_SIZE,
+                dim: DEFAULT_DIM,
+            })
+        );
+
+        // 未知のオプションをコーパスのファイルとして扱わない
+        assert_eq!(
+            parse_args(&args("ptb.txt --windows 3")),
+            Err("unknown option: --windows".to_string())
+        );
+        assert_eq!(
+            parse_args(&args("--dim")),
+            Err("--dim requires a value".to_string())
+        );
+        assert_eq!(
+            parse_args(&args("--load")),
+            Err("--load requires a value".to_string())
+        );
+        assert!(parse_args(&args("--dim ten ptb.txt")).is_err());
+        assert!(parse_args(&args("a.txt b.txt")).is_err());
+        assert!(parse_args(&args("ptb.txt --load vectors.txt")).is_err());
+        assert!(parse_args(&args("")).is_err());
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert!(matches!(parse_command("  "), Ok(Command::Empty)));
+        assert!(matches!(parse_command("exit"), Ok(Command::Quit)));
+        assert!(matches!(parse_command("top 10"), Ok(Command::Top(10))));
+        assert!(matches!(
+            parse_command("metric euclid"),
+            Ok(Command::Metric(SimilarityMetric::Euclidean))
+        ));
+        assert!(matches!(
+            parse_command("method mul"),
+            Ok(Command::Method(AnalogyMethod::CosMul))
+        ));
+        assert!(matches!(
+            parse_command("matrix svd"),
+            Ok(Command::Matrix(MatrixKind::Svd))
+        ));
+
+        let query = |line| match parse_command(line) {
+            Ok(Command::Query(query)) => Some(query),
+            _ => None,
+        };
+        assert_eq!(query("you"), Some(Query::MostSimilar("you")));
+        assert_eq!(query("sim you"), Some(Query::MostSimilar("you")));
+        assert_eq!(query("pair you i"), Some(Query::Pair("you", "i")));
+        assert_eq!(
+            query("analogy man king woman"),
+            Some(Query::Analogy("man", "king", "woman"))
+        );
+
+        assert!(parse_command("top many").is_err());
+        assert_eq!(
+            parse_command("matrix dense").err(),
+            Some("unknown matrix: dense".to_string())
+        );
+        assert!(parse_command("metric manhattan").is_err());
+        assert!(parse_command("sim you me").is_err());
+    }
+}
@@ -1,51 +1,135 @@
 use std::collections::HashMap;
 
-pub(super) type WordId = usize;
-pub(super) type Word = String;
+use ndarray::{Array1, Array2};
+
+use self::tokenizer::{DefaultTokenizer, Tokenizer};
+
+pub mod bptt_dataset;
+pub mod contexts_target_dataset;
+pub mod reader;
+pub mod subsampling;
+pub mod tokenizer;
+pub mod vocabulary;
+pub mod window;
+
+pub type WordId = usize;
+pub type Word = String;
 
 pub struct Corpus {
     pub(super) text: Vec<WordId>,
     pub(super) word_to_id: HashMap<Word, WordId>,
     pub(super) id_to_word: HashMap<WordId, Word>,
+    // 各単語の出現回数（単語 ID で添字づけ）
+    word_counts: Vec<usize>,
 }
 
 impl Corpus {
     pub fn new(text: &str) -> Self {
-        let text = text.to_lowercase();
-        let text = text.replace(".", " .");
-        let words = text.split_whitespace();
-
-        let mut id_to_word = HashMap::<WordId, Word>::new();
-        let mut word_to_id = HashMap::<Word, WordId>::new();
-        let mut new_id = 0;
-
-        let text = words
-            .map(|word| {
-                let word_id = match word_to_id.get(word) {
-                    Some(id) => *id,
-                    None => {
-                        let id = new_id;
-                        new_id += 1;
-                        word_to_id.insert(word.to_string(), id);
-                        id_to_word.insert(id, word.to_string());
-                        id
-                    }
-                };
-                word_id
-            })
-            .collect::<Vec<WordId>>();
+        Self::with_tokenizer(text, &DefaultTokenizer)
+    }
 
+    pub fn with_tokenizer<T: Tokenizer>(text: &str, tokenizer: &T) -> Self {
+        Self::from_words(tokenizer.tokenize(text))
+    }
+
+    // 出現順に単語 ID を割り振る
+    fn from_words<I: IntoIterator<Item = Word>>(words: I) -> Self {
+        let mut corpus = Self::empty();
+        for word in words {
+            corpus.push_word(word);
+        }
+        corpus
+    }
+
+    fn empty() -> Self {
         Self {
-            text,
-            word_to_id,
-            id_to_word,
+            text: vec![],
+            word_to_id: HashMap::new(),
+            id_to_word: HashMap::new(),
+            word_counts: vec![],
         }
     }
+
+    // テキストを持たず、語彙だけを持つコーパス（保存した単語ベクトルの読み込みなどに用いる）
+    pub(crate) fn from_vocabulary<I: IntoIterator<Item = Word>>(words: I) -> Self {
+        let mut corpus = Self::empty();
+        for word in words {
+            corpus.word_id_or_insert(word);
+        }
+        corpus
+    }
+
+    fn push_word(&mut self, word: Word) {
+        let word_id = self.word_id_or_insert(word);
+        self.word_counts[word_id] += 1;
+        self.text.push(word_id);
+    }
+
+    // 初めて現れた単語には新しい単語 ID を割り振る
+    fn word_id_or_insert(&mut self, word: Word) -> WordId {
+        match self.word_to_id.get(&word) {
+            Some(id) => *id,
+            None => {
+                let id = self.id_to_word.len();
+                self.word_to_id.insert(word.clone(), id);
+                self.id_to_word.insert(id, word);
+                self.word_counts.push(0);
+                id
+            }
+        }
+    }
+
+    pub fn vocab_size(&self) -> usize {
+        self.id_to_word.len()
+    }
+
+    pub fn word_to_id(&self, word: &str) -> Option<WordId> {
+        self.word_to_id.get(word).copied()
+    }
+
+    pub fn id_to_word(&self, word_id: WordId) -> Option<&Word> {
+        self.id_to_word.get(&word_id)
+    }
+
+    pub fn word_counts(&self) -> &[usize] {
+        &self.word_counts
+    }
+
+    // 各単語をターゲットとし、その左右 window_size 個の単語をコンテキストとして取り出す
+    // contexts: (ターゲット数, 2 * window_size), target: (ターゲット数)
+    pub fn create_contexts_target(&self, window_size: usize) -> (Array2<WordId>, Array1<WordId>) {
+        let number_of_targets = self.text.len().saturating_sub(2 * window_size);
+
+        // テキストが短すぎる場合は空になる
+        let target = self
+            .text
+            .iter()
+            .skip(window_size)
+            .take(number_of_targets)
+            .copied()
+            .collect::<Vec<WordId>>();
+
+        let contexts = (window_size..(window_size + number_of_targets))
+            .flat_map(|idx| {
+                (1..=window_size)
+                    .rev()
+                    .map(move |i| idx - i)
+                    .chain((1..=window_size).map(move |i| idx + i))
+            })
+            .map(|idx| self.text[idx])
+            .collect::<Vec<WordId>>();
+        let contexts =
+            Array2::from_shape_vec((number_of_targets, 2 * window_size), contexts).unwrap();
+
+        (contexts, Array1::from(target))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use ndarray::array;
+
+    use super::{tokenizer::PunctuationTokenizer, *};
 
     #[test]
     fn test_corpus() {
@@ -75,5 +159,46 @@ mod tests {
         expected_id_to_word.insert(6, ".".to_string());
 
         assert_eq!(corpus.id_to_word, expected_id_to_word);
+
+        assert_eq!(corpus.word_counts(), &[1, 2, 1, 1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn test_corpus_with_tokenizer() {
+        let text = "\"You say goodbye,\" and I say hello.";
+        let corpus = Corpus::with_tokenizer(text, &PunctuationTokenizer::new());
+
+        assert_eq!(corpus.vocab_size(), 9);
+        assert_eq!(corpus.text, vec![0, 1, 2, 3, 4, 0, 5, 6, 2, 7, 8]);
+        assert_eq!(corpus.word_to_id("goodbye"), Some(3));
+        assert_eq!(corpus.word_to_id("goodbye,"), None);
+        assert_eq!(corpus.id_to_word(4), Some(&",".to_string()));
+    }
+
+    #[test]
+    fn test_create_contexts_target() {
+        let text = "You say goodbye and I say hello.";
+        let corpus = Corpus::new(text);
+        let (contexts, target) = corpus.create_contexts_target(1);
+
+        assert_eq!(
+            contexts,
+            array![[0, 2], [1, 3], [2, 4], [3, 1], [4, 5], [1, 6]]
+        );
+        assert_eq!(target, array![1, 2, 3, 4, 1, 5]);
+
+        let (contexts, target) = corpus.create_contexts_target(2);
+        assert_eq!(
+            contexts,
+            array![[0, 1, 3, 4], [1, 2, 4, 1], [2, 3, 1, 5], [3, 4, 5, 6]]
+        );
+        assert_eq!(target, array![2, 3, 4, 1]);
+
+        // コンテキストを取れない短いテキスト
+        for window_size in [4, 8, 10] {
+            let (contexts, target) = corpus.create_contexts_target(window_size);
+            assert_eq!(contexts.dim(), (0, 2 * window_size));
+            assert_eq!(target.len(), 0);
+        }
     }
 }
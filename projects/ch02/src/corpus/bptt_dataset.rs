@@ -0,0 +1,183 @@
+/*
+    言語モデルを Truncated BPTT で学習するためのデータセット
+    入力はコーパスの単語 text[i]、正解はその次の単語 text[i + 1]
+
+    コーパスをバッチサイズ個の区間に分け、バッチの n 番目の系列は
+    n * (データ数 / バッチサイズ) の位置から読み始める
+    各ミニバッチは直前のミニバッチの続きの time_size 単語なので、隠れ状態を引き継げる
+*/
+
+use neural_network::dataset::dataset::{TimeSeriesDataset, TimeSeriesMiniBatch};
+
+use super::{Corpus, WordId};
+
+pub struct BpttDataset {
+    // 学習用・テスト用の入力と正解
+    train: (Vec<WordId>, Vec<WordId>),
+    test: (Vec<WordId>, Vec<WordId>),
+    batch_size: usize,
+    time_size: usize,
+    cursor: usize,
+}
+
+pub struct InitParamsOfBpttDataset {
+    pub batch_size: usize,
+    pub time_size: usize,
+    // コーパス末尾からテスト用に取り分ける割合
+    pub test_ratio: f32,
+}
+
+impl BpttDataset {
+    pub fn new(corpus: &Corpus, params: InitParamsOfBpttDataset) -> Self {
+        let InitParamsOfBpttDataset {
+            batch_size,
+            time_size,
+            test_ratio,
+        } = params;
+
+        assert!(batch_size > 0 && time_size > 0);
+        assert!((0. ..1.).contains(&test_ratio));
+
+        // 入力と正解の組を１つ以上作れることを要請
+        let text = &corpus.text;
+        assert!(text.len() >= 2);
+        let xs = &text[..(text.len() - 1)];
+        let ts = &text[1..];
+        let data_size = xs.len();
+        let number_of_tests = (data_size as f32 * test_ratio) as usize;
+        let number_of_trains = data_size - number_of_tests;
+
+        Self {
+            train: (
+                xs[..number_of_trains].to_vec(),
+                ts[..number_of_trains].to_vec(),
+            ),
+            test: (
+                xs[number_of_trains..].to_vec(),
+                ts[number_of_trains..].to_vec(),
+            ),
+            batch_size,
+            time_size,
+            cursor: 0,
+        }
+    }
+
+    fn number_of_batches(&self, data_size: usize) -> usize {
+        data_size / (self.batch_size * self.time_size)
+    }
+
+    // iter 番目のミニバッチ
+    fn mini_batch(
+        &self,
+        (xs, ts): &(Vec<WordId>, Vec<WordId>),
+        iter: usize,
+    ) -> TimeSeriesMiniBatch {
+        let data_size = xs.len();
+        let jump = data_size / self.batch_size;
+        let rows = |source: &[WordId]| {
+            (0..self.batch_size)
+                .map(|n| {
+                    (0..self.time_size)
+                        .map(|t| source[(n * jump + iter * self.time_size + t) % data_size])
+                        .collect()
+                })
+                .collect()
+        };
+
+        TimeSeriesMiniBatch {
+            inputs: rows(xs),
+            labels: rows(ts),
+        }
+    }
+}
+
+impl TimeSeriesDataset for BpttDataset {
+    fn reset_cursor(&mut self) {
+        self.cursor = 0;
+    }
+
+    fn test_data(&self) -> Vec<TimeSeriesMiniBatch> {
+        (0..self.number_of_batches(self.test.0.len()))
+            .map(|iter| self.mini_batch(&self.test, iter))
+            .collect()
+    }
+}
+
+impl Iterator for BpttDataset {
+    type Item = TimeSeriesMiniBatch;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.cursor >= self.len() {
+            None
+        } else {
+            let mini_batch = self.mini_batch(&self.train, self.cursor);
+            self.cursor += 1;
+            Some(mini_batch)
+        }
+    }
+}
+
+impl ExactSizeIterator for BpttDataset {
+    fn len(&self) -> usize {
+        self.number_of_batches(self.train.0.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bptt_dataset() {
+        // 単語 ID は 0, 1, ..., 12
+        let text = "a b c d e f g h i j k l m";
+        let corpus = Corpus::new(text);
+
+        let mut dataset = BpttDataset::new(
+            &corpus,
+            InitParamsOfBpttDataset {
+                batch_size: 2,
+                time_size: 3,
+                test_ratio: 0.,
+            },
+        );
+        // 入力は 12 単語なので、２つの系列は 0 番目と 6 番目から始まる
+        assert_eq!(dataset.len(), 2);
+
+        let TimeSeriesMiniBatch { inputs, labels } = dataset.next().unwrap();
+        assert_eq!(inputs, vec![vec![0, 1, 2], vec![6, 7, 8]]);
+        assert_eq!(labels, vec![vec![1, 2, 3], vec![7, 8, 9]]);
+
+        // 次のミニバッチは各系列の続き
+        let TimeSeriesMiniBatch { inputs, labels } = dataset.next().unwrap();
+        assert_eq!(inputs, vec![vec![3, 4, 5], vec![9, 10, 11]]);
+        assert_eq!(labels, vec![vec![4, 5, 6], vec![10, 11, 12]]);
+
+        assert!(dataset.next().is_none());
+        assert!(dataset.test_data().is_empty());
+        dataset.reset_cursor();
+        assert_eq!(dataset.count(), 2);
+    }
+
+    #[test]
+    fn test_bptt_dataset_test_data() {
+        let text = "a b c d e f g h i j k l m";
+        let corpus = Corpus::new(text);
+
+        // 末尾の４単語をテスト用に取り分ける
+        let dataset = BpttDataset::new(
+            &corpus,
+            InitParamsOfBpttDataset {
+                batch_size: 2,
+                time_size: 2,
+                test_ratio: 0.34,
+            },
+        );
+        assert_eq!(dataset.len(), 2);
+
+        let test_data = dataset.test_data();
+        assert_eq!(test_data.len(), 1);
+        assert_eq!(test_data[0].inputs, vec![vec![8, 9], vec![10, 11]]);
+        assert_eq!(test_data[0].labels, vec![vec![9, 10], vec![11, 12]]);
+    }
+}
@@ -0,0 +1,251 @@
+use std::marker::PhantomData;
+
+use neural_network::{
+    dataset::dataset::{Dataset, MiniBatch},
+    matrix::{matrix_one_dim::MatrixOneDim, matrix_two_dim::MatrixTwoDim},
+};
+use rand::{seq::SliceRandom, Rng};
+
+use super::{Corpus, WordId};
+
+// ミニバッチの入力におけるコンテキストの表現方法
+#[derive(Clone, Copy)]
+pub enum ContextsEncoding {
+    // 各コンテキストの one-hot 表現を連結する: (バッチサイズ, 2 * window_size * 語彙数)
+    OneHot,
+    // 単語 ID をそのまま並べる: (バッチサイズ, 2 * window_size)
+    Id,
+}
+
+impl ContextsEncoding {
+    fn encode(&self, contexts: &[WordId], vocab_size: usize) -> Vec<f32> {
+        match self {
+            ContextsEncoding::OneHot => {
+                let mut encoded = vec![0.; contexts.len() * vocab_size];
+                for (i, &word_id) in contexts.iter().enumerate() {
+                    encoded[i * vocab_size + word_id] = 1.;
+                }
+                encoded
+            }
+            ContextsEncoding::Id => contexts.iter().map(|&word_id| word_id as f32).collect(),
+        }
+    }
+}
+
+pub struct ContextsTargetDataset<M2, M1, R> {
+    contexts: Vec<Vec<WordId>>,
+    target: Vec<WordId>,
+    // 学習用・テスト用データのインデックス
+    train_indices: Vec<usize>,
+    test_indices: Vec<usize>,
+    vocab_size: usize,
+    encoding: ContextsEncoding,
+    cursor: usize,
+    batch_size: usize,
+    // 学習用データのシャッフルに使う
+    rng: R,
+    phantom: PhantomData<(M2, M1)>,
+}
+
+pub struct InitParamsOfContextsTargetDataset {
+    pub batch_size: usize,
+    pub window_size: usize,
+    pub encoding: ContextsEncoding,
+    // コーパス末尾からテスト用に取り分ける割合
+    pub test_ratio: f32,
+}
+
+impl<M2, M1, R> ContextsTargetDataset<M2, M1, R>
+where
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+    R: Rng,
+{
+    pub fn new(corpus: &Corpus, params: InitParamsOfContextsTargetDataset, rng: R) -> Self {
+        let InitParamsOfContextsTargetDataset {
+            batch_size,
+            window_size,
+            encoding,
+            test_ratio,
+        } = params;
+
+        assert!(batch_size > 0);
+        assert!((0. ..1.).contains(&test_ratio));
+
+        let (contexts, target) = corpus.create_contexts_target(window_size);
+        let contexts = contexts
+            .rows()
+            .into_iter()
+            .map(|row| row.to_vec())
+            .collect::<Vec<_>>();
+        let target = target.to_vec();
+
+        let number_of_pairs = target.len();
+        let number_of_tests = (number_of_pairs as f32 * test_ratio) as usize;
+        let train_indices = (0..(number_of_pairs - number_of_tests)).collect();
+        let test_indices = ((number_of_pairs - number_of_tests)..number_of_pairs).collect();
+
+        Self {
+            contexts,
+            target,
+            train_indices,
+            test_indices,
+            vocab_size: corpus.vocab_size(),
+            encoding,
+            cursor: 0,
+            batch_size,
+            rng,
+            phantom: PhantomData,
+        }
+    }
+
+    fn mini_batch(&self, indices: &[usize]) -> MiniBatch<M2, M1> {
+        let bundled_inputs: Vec<M1> = indices
+            .iter()
+            .map(|&index| M1::from(self.encoding.encode(&self.contexts[index], self.vocab_size)))
+            .collect();
+        let bundled_inputs = M2::from_1d_arrays(bundled_inputs);
+
+        let bundled_one_hot_labels: Vec<M1> = indices
+            .iter()
+            .map(|&index| {
+                let mut one_hot = vec![0.; self.vocab_size];
+                one_hot[self.target[index]] = 1.;
+                M1::from(one_hot)
+            })
+            .collect();
+        let bundled_one_hot_labels = M2::from_1d_arrays(bundled_one_hot_labels);
+
+        MiniBatch {
+            bundled_inputs,
+            bundled_one_hot_labels,
+            ph: PhantomData,
+        }
+    }
+}
+
+impl<M2, M1, R> Dataset<M2, M1> for ContextsTargetDataset<M2, M1, R>
+where
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+    R: Rng,
+{
+    fn shuffle_and_reset_cursor(&mut self) {
+        self.train_indices.shuffle(&mut self.rng);
+        self.cursor = 0;
+    }
+
+    fn test_data(&self) -> MiniBatch<M2, M1> {
+        self.mini_batch(&self.test_indices)
+    }
+}
+
+impl<M2, M1, R> Iterator for ContextsTargetDataset<M2, M1, R>
+where
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+    R: Rng,
+{
+    type Item = MiniBatch<M2, M1>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let number_of_pairs = self.train_indices.len();
+        let rest = number_of_pairs - self.cursor;
+        if rest < self.batch_size {
+            None
+        } else {
+            let mini_batch =
+                self.mini_batch(&self.train_indices[self.cursor..(self.cursor + self.batch_size)]);
+            self.cursor += self.batch_size;
+            Some(mini_batch)
+        }
+    }
+}
+
+impl<M2, M1, R> ExactSizeIterator for ContextsTargetDataset<M2, M1, R>
+where
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+    R: Rng,
+{
+    fn len(&self) -> usize {
+        self.train_indices.len() / self.batch_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Array1, Array2};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn test_contexts_target_dataset() {
+        let text = "You say goodbye and I say hello.";
+        let corpus = Corpus::new(text);
+
+        // one-hot 表現
+        let mut dataset: ContextsTargetDataset<Array2<f32>, Array1<f32>, StdRng> =
+            ContextsTargetDataset::new(
+                &corpus,
+                InitParamsOfContextsTargetDataset {
+                    batch_size: 2,
+                    window_size: 1,
+                    encoding: ContextsEncoding::OneHot,
+                    test_ratio: 0.,
+                },
+                StdRng::seed_from_u64(0),
+            );
+        assert_eq!(dataset.len(), 3);
+
+        let MiniBatch {
+            bundled_inputs,
+            bundled_one_hot_labels,
+            ph: _,
+        } = dataset.next().unwrap();
+        assert_eq!(
+            bundled_inputs,
+            array![
+                [1., 0., 0., 0., 0., 0., 0., 0., 0., 1., 0., 0., 0., 0.],
+                [0., 1., 0., 0., 0., 0., 0., 0., 0., 0., 1., 0., 0., 0.],
+            ]
+        );
+        assert_eq!(
+            bundled_one_hot_labels,
+            array![[0., 1., 0., 0., 0., 0., 0.], [0., 0., 1., 0., 0., 0., 0.]]
+        );
+
+        // 単語 ID 表現、末尾の２組はテスト用に取り分けられる
+        let mut dataset: ContextsTargetDataset<Array2<f32>, Array1<f32>, StdRng> =
+            ContextsTargetDataset::new(
+                &corpus,
+                InitParamsOfContextsTargetDataset {
+                    batch_size: 2,
+                    window_size: 1,
+                    encoding: ContextsEncoding::Id,
+                    test_ratio: 0.34,
+                },
+                StdRng::seed_from_u64(0),
+            );
+        assert_eq!(dataset.len(), 2);
+
+        let MiniBatch { bundled_inputs, .. } = dataset.next().unwrap();
+        assert_eq!(bundled_inputs, array![[0., 2.], [1., 3.]]);
+
+        let MiniBatch {
+            bundled_inputs,
+            bundled_one_hot_labels,
+            ph: _,
+        } = dataset.test_data();
+        assert_eq!(bundled_inputs, array![[4., 5.], [1., 6.]]);
+        assert_eq!(
+            bundled_one_hot_labels,
+            array![[0., 1., 0., 0., 0., 0., 0.], [0., 0., 0., 0., 0., 1., 0.]]
+        );
+
+        // シャッフルしても学習用データの数は変わらない
+        dataset.shuffle_and_reset_cursor();
+        assert_eq!(dataset.count(), 2);
+    }
+}
@@ -0,0 +1,103 @@
+/*
+    BufRead から１行ずつ読み込んで Corpus を作る
+    テキスト全体を String として保持せずに済むので、大きなファイルも扱える
+
+    end_of_sentence を指定すると、各行（空行を除く）の末尾にその単語を加える
+    （PTB データセットでは "<eos>"）
+*/
+
+use std::io::{self, BufRead};
+
+use super::{tokenizer::Tokenizer, Corpus, Word};
+
+pub const END_OF_SENTENCE: &str = "<eos>";
+
+pub struct InitParamsOfReader {
+    pub end_of_sentence: Option<Word>,
+}
+
+impl Corpus {
+    pub fn from_reader<R: BufRead, T: Tokenizer>(
+        reader: R,
+        tokenizer: &T,
+        params: InitParamsOfReader,
+    ) -> io::Result<Self> {
+        Self::from_readers([reader], tokenizer, params)
+    }
+
+    // 複数のファイルを順に連結して１つのコーパスとする
+    pub fn from_readers<I, R, T>(
+        readers: I,
+        tokenizer: &T,
+        params: InitParamsOfReader,
+    ) -> io::Result<Self>
+    where
+        I: IntoIterator<Item = R>,
+        R: BufRead,
+        T: Tokenizer,
+    {
+        let InitParamsOfReader { end_of_sentence } = params;
+        let mut corpus = Self::empty();
+
+        for reader in readers {
+            for line in reader.lines() {
+                let words = tokenizer.tokenize(&line?);
+                if words.is_empty() {
+                    continue;
+                }
+
+                for word in words {
+                    corpus.push_word(word);
+                }
+                if let Some(end_of_sentence) = &end_of_sentence {
+                    corpus.push_word(end_of_sentence.clone());
+                }
+            }
+        }
+
+        Ok(corpus)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::corpus::tokenizer::{DefaultTokenizer, WhitespaceTokenizer};
+
+    use super::*;
+
+    #[test]
+    fn test_from_reader() {
+        let text = "You say goodbye and I say hello.";
+        let corpus = Corpus::from_reader(
+            text.as_bytes(),
+            &DefaultTokenizer,
+            InitParamsOfReader {
+                end_of_sentence: None,
+            },
+        )
+        .unwrap();
+        let expected = Corpus::new(text);
+
+        assert_eq!(corpus.text, expected.text);
+        assert_eq!(corpus.word_to_id, expected.word_to_id);
+        assert_eq!(corpus.id_to_word, expected.id_to_word);
+        assert_eq!(corpus.word_counts, expected.word_counts);
+    }
+
+    #[test]
+    fn test_from_readers_with_end_of_sentence() {
+        let files = ["a b\n\nb c\n", "c a"];
+        let corpus = Corpus::from_readers(
+            files.iter().map(|file| file.as_bytes()),
+            &WhitespaceTokenizer,
+            InitParamsOfReader {
+                end_of_sentence: Some(END_OF_SENTENCE.to_string()),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(corpus.vocab_size(), 4);
+        assert_eq!(corpus.word_to_id(END_OF_SENTENCE), Some(2));
+        assert_eq!(corpus.text, vec![0, 1, 2, 1, 3, 2, 3, 0, 2]);
+    }
+}
@@ -0,0 +1,82 @@
+/*
+    頻出語のサブサンプリング (Mikolov et al., 2013)
+
+    出現頻度 f(w) = count(w) / Σ count の単語 w を、確率
+    P_discard(w) = max(0, 1 - sqrt(threshold / f(w)))
+    でテキストから取り除く（word2vec では threshold = 1e-5 程度）
+
+    語彙と単語 ID は元のコーパスのまま保つので、
+    取り除いた後のコーパスから作った CoMatrix などは元のコーパスの単語 ID で引ける
+*/
+
+use rand::Rng;
+
+use super::Corpus;
+
+impl Corpus {
+    pub fn subsample<R: Rng>(&self, threshold: f32, rng: &mut R) -> Corpus {
+        let total = self.text.len() as f32;
+        let discard_p = self
+            .word_counts
+            .iter()
+            .map(|&count| {
+                let frequency = count as f32 / total;
+                (1. - (threshold / frequency).sqrt()).max(0.)
+            })
+            .collect::<Vec<_>>();
+
+        let text = self
+            .text
+            .iter()
+            .copied()
+            .filter(|&word_id| rng.gen::<f32>() >= discard_p[word_id])
+            .collect::<Vec<_>>();
+
+        let mut word_counts = vec![0; self.vocab_size()];
+        for &word_id in &text {
+            word_counts[word_id] += 1;
+        }
+
+        Corpus {
+            text,
+            word_to_id: self.word_to_id.clone(),
+            id_to_word: self.id_to_word.clone(),
+            word_counts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn test_subsample() {
+        // "the" が全体の 9 割を占める
+        let text = format!("{} a b c d e f g h i j", "the ".repeat(99));
+        let corpus = Corpus::new(&text);
+        let the_id = corpus.word_to_id("the").unwrap();
+
+        let subsampled = corpus.subsample(0.01, &mut StdRng::seed_from_u64(0));
+
+        // 語彙は変わらない
+        assert_eq!(subsampled.vocab_size(), corpus.vocab_size());
+        assert_eq!(subsampled.word_to_id, corpus.word_to_id);
+
+        // 出現頻度が threshold 以下の単語は取り除かれない
+        (0..corpus.vocab_size())
+            .filter(|&word_id| word_id != the_id)
+            .for_each(|word_id| assert_eq!(subsampled.word_counts()[word_id], 1));
+
+        // "the" は 1 - sqrt(0.01 / 0.9) ≒ 0.89 の確率で取り除かれる
+        let the_count = subsampled.word_counts()[the_id];
+        assert!(0 < the_count && the_count < 30);
+        assert_eq!(subsampled.text.len(), the_count + 10);
+
+        // 同じシードなら同じ結果になる
+        let again = corpus.subsample(0.01, &mut StdRng::seed_from_u64(0));
+        assert_eq!(again.text, subsampled.text);
+    }
+}
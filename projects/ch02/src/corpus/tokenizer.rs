@@ -0,0 +1,198 @@
+/*
+    文字列を単語列に分割する
+
+    - WhitespaceTokenizer: 空白で区切る
+    - RegexTokenizer: 正規表現にマッチする部分を単語とする
+    - PunctuationTokenizer: 記号を単語から切り離す（単語中のアポストロフィは残す）
+    - DefaultTokenizer: 小文字化したうえで "." だけを切り離して空白で区切る（Corpus::new の既定）
+
+    Normalized で包むと、分割の前に小文字化・Unicode 正規化を行う
+*/
+
+use regex::Regex;
+use unicode_normalization::UnicodeNormalization;
+
+use super::Word;
+
+pub trait Tokenizer {
+    fn tokenize(&self, text: &str) -> Vec<Word>;
+}
+
+pub struct WhitespaceTokenizer;
+
+impl Tokenizer for WhitespaceTokenizer {
+    fn tokenize(&self, text: &str) -> Vec<Word> {
+        text.split_whitespace()
+            .map(|word| word.to_string())
+            .collect()
+    }
+}
+
+pub struct RegexTokenizer(Regex);
+
+impl RegexTokenizer {
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Self(Regex::new(pattern)?))
+    }
+}
+
+impl Tokenizer for RegexTokenizer {
+    fn tokenize(&self, text: &str) -> Vec<Word> {
+        self.0
+            .find_iter(text)
+            .map(|m| m.as_str().to_string())
+            .collect()
+    }
+}
+
+// "don't" のように単語の内側にあるアポストロフィは単語の一部とみなし、
+// それ以外の記号は１文字ずつ単語とする
+const PUNCTUATION_PATTERN: &str = r"\w+(?:['’]\w+)*|[^\w\s]";
+
+pub struct PunctuationTokenizer(RegexTokenizer);
+
+impl PunctuationTokenizer {
+    pub fn new() -> Self {
+        Self(RegexTokenizer::new(PUNCTUATION_PATTERN).unwrap())
+    }
+}
+
+impl Default for PunctuationTokenizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tokenizer for PunctuationTokenizer {
+    fn tokenize(&self, text: &str) -> Vec<Word> {
+        self.0.tokenize(text)
+    }
+}
+
+pub struct DefaultTokenizer;
+
+impl Tokenizer for DefaultTokenizer {
+    fn tokenize(&self, text: &str) -> Vec<Word> {
+        let text = text.to_lowercase();
+        let text = text.replace('.', " .");
+        WhitespaceTokenizer.tokenize(&text)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum UnicodeNormalizationForm {
+    Nfc,
+    Nfd,
+    Nfkc,
+    Nfkd,
+}
+
+pub struct InitParamsOfNormalized {
+    pub lowercase: bool,
+    pub unicode_normalization: Option<UnicodeNormalizationForm>,
+}
+
+pub struct Normalized<T: Tokenizer> {
+    tokenizer: T,
+    lowercase: bool,
+    unicode_normalization: Option<UnicodeNormalizationForm>,
+}
+
+impl<T: Tokenizer> Normalized<T> {
+    pub fn new(tokenizer: T, params: InitParamsOfNormalized) -> Self {
+        let InitParamsOfNormalized {
+            lowercase,
+            unicode_normalization,
+        } = params;
+
+        Self {
+            tokenizer,
+            lowercase,
+            unicode_normalization,
+        }
+    }
+}
+
+impl<T: Tokenizer> Tokenizer for Normalized<T> {
+    fn tokenize(&self, text: &str) -> Vec<Word> {
+        // 全角記号なども分割の対象になるよう、正規化してから分割する
+        let text = match self.unicode_normalization {
+            None => text.to_string(),
+            Some(UnicodeNormalizationForm::Nfc) => text.nfc().collect(),
+            Some(UnicodeNormalizationForm::Nfd) => text.nfd().collect(),
+            Some(UnicodeNormalizationForm::Nfkc) => text.nfkc().collect(),
+            Some(UnicodeNormalizationForm::Nfkd) => text.nfkd().collect(),
+        };
+        let text = if self.lowercase {
+            text.to_lowercase()
+        } else {
+            text
+        };
+        self.tokenizer.tokenize(&text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenizers() {
+        let text = "\"Hello,\" she said. I don't know.";
+
+        assert_eq!(
+            WhitespaceTokenizer.tokenize(text),
+            vec!["\"Hello,\"", "she", "said.", "I", "don't", "know."]
+        );
+        assert_eq!(
+            DefaultTokenizer.tokenize(text),
+            vec!["\"hello,\"", "she", "said", ".", "i", "don't", "know", "."]
+        );
+        assert_eq!(
+            PunctuationTokenizer::new().tokenize(text),
+            vec!["\"", "Hello", ",", "\"", "she", "said", ".", "I", "don't", "know", "."]
+        );
+        assert_eq!(
+            RegexTokenizer::new(r"[A-Za-z]+").unwrap().tokenize(text),
+            vec!["Hello", "she", "said", "I", "don", "t", "know"]
+        );
+        assert!(RegexTokenizer::new(r"(").is_err());
+    }
+
+    #[test]
+    fn test_normalized() {
+        // 全角英字・全角コンマ
+        let text = "Ｈｅｌｌｏ，Ｗｏｒｌｄ";
+
+        let tokenizer = Normalized::new(
+            PunctuationTokenizer::new(),
+            InitParamsOfNormalized {
+                lowercase: true,
+                unicode_normalization: Some(UnicodeNormalizationForm::Nfkc),
+            },
+        );
+        assert_eq!(tokenizer.tokenize(text), vec!["hello", ",", "world"]);
+
+        let tokenizer = Normalized::new(
+            WhitespaceTokenizer,
+            InitParamsOfNormalized {
+                lowercase: false,
+                unicode_normalization: None,
+            },
+        );
+        assert_eq!(tokenizer.tokenize(text), vec![text]);
+
+        // 合成済み文字と結合文字列は NFC で同じ単語になる
+        let tokenizer = Normalized::new(
+            WhitespaceTokenizer,
+            InitParamsOfNormalized {
+                lowercase: false,
+                unicode_normalization: Some(UnicodeNormalizationForm::Nfc),
+            },
+        );
+        assert_eq!(
+            tokenizer.tokenize("caf\u{e9} cafe\u{301}"),
+            vec!["caf\u{e9}", "caf\u{e9}"]
+        );
+    }
+}
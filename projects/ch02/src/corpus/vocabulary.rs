@@ -0,0 +1,140 @@
+/*
+    語彙の刈り込み
+
+    出現回数が min_count 未満の単語と、出現回数の多い順に max_size 個に入らなかった単語を
+    すべて UNKNOWN_WORD ("<unk>") に置き換え、単語 ID を振り直す
+    刈り込んだコーパスでは、テキストに現れなくても UNKNOWN_WORD に UNKNOWN_WORD_ID (0) を割り振る
+*/
+
+use super::{Corpus, WordId};
+
+pub const UNKNOWN_WORD: &str = "<unk>";
+pub const UNKNOWN_WORD_ID: WordId = 0;
+
+pub struct InitParamsOfVocabulary {
+    pub min_count: usize,
+    // UNKNOWN_WORD を除いた語彙数の上限
+    pub max_size: Option<usize>,
+}
+
+impl Corpus {
+    pub fn prune_vocabulary(&self, params: InitParamsOfVocabulary) -> Corpus {
+        let InitParamsOfVocabulary {
+            min_count,
+            max_size,
+        } = params;
+
+        let unknown_word_id = self.unknown_word_id();
+
+        // 出現回数の多い順（同数なら ID の小さい順）に残す単語を選ぶ
+        let mut candidates = (0..self.vocab_size())
+            .filter(|&word_id| Some(word_id) != unknown_word_id)
+            .filter(|&word_id| self.word_counts[word_id] >= min_count)
+            .collect::<Vec<WordId>>();
+        candidates.sort_by_key(|&word_id| (std::cmp::Reverse(self.word_counts[word_id]), word_id));
+        if let Some(max_size) = max_size {
+            candidates.truncate(max_size);
+        }
+
+        let mut kept = vec![false; self.vocab_size()];
+        for word_id in candidates {
+            kept[word_id] = true;
+        }
+
+        let mut pruned = Corpus::from_vocabulary([UNKNOWN_WORD.to_string()]);
+        for &word_id in &self.text {
+            if kept[word_id] {
+                pruned.push_word(self.id_to_word[&word_id].clone());
+            } else {
+                pruned.push_word(UNKNOWN_WORD.to_string());
+            }
+        }
+        pruned
+    }
+
+    // 刈り込んだコーパスでは UNKNOWN_WORD_ID
+    pub fn unknown_word_id(&self) -> Option<WordId> {
+        self.word_to_id(UNKNOWN_WORD)
+    }
+
+    // 語彙にない単語は UNKNOWN_WORD として扱う
+    // UNKNOWN_WORD も語彙にない（刈り込んでいない）コーパスでは None
+    pub fn word_to_id_or_unknown(&self, word: &str) -> Option<WordId> {
+        self.word_to_id(word).or_else(|| self.unknown_word_id())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(corpus: &Corpus) -> Vec<&str> {
+        corpus
+            .text
+            .iter()
+            .map(|&word_id| corpus.id_to_word(word_id).unwrap().as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_prune_vocabulary() {
+        let text = "a b a c a b d <unk> e";
+        let corpus = Corpus::new(text);
+
+        // 刈り込まない
+        let pruned = corpus.prune_vocabulary(InitParamsOfVocabulary {
+            min_count: 1,
+            max_size: None,
+        });
+        assert_eq!(words(&pruned), words(&corpus));
+        assert_eq!(pruned.unknown_word_id(), Some(UNKNOWN_WORD_ID));
+
+        // min_count
+        let pruned = corpus.prune_vocabulary(InitParamsOfVocabulary {
+            min_count: 2,
+            max_size: None,
+        });
+        assert_eq!(pruned.vocab_size(), 3);
+        assert_eq!(pruned.text, vec![1, 2, 1, 0, 1, 2, 0, 0, 0]);
+        assert_eq!(pruned.unknown_word_id(), Some(UNKNOWN_WORD_ID));
+        assert_eq!(pruned.word_counts(), &[4, 3, 2]);
+
+        // max_size
+        let pruned = corpus.prune_vocabulary(InitParamsOfVocabulary {
+            min_count: 1,
+            max_size: Some(1),
+        });
+        assert_eq!(pruned.vocab_size(), 2);
+        assert_eq!(pruned.word_to_id("a"), Some(1));
+        assert_eq!(pruned.word_to_id("b"), None);
+        assert_eq!(pruned.word_counts(), &[6, 3]);
+    }
+
+    #[test]
+    fn test_unknown_word_is_always_reserved() {
+        // 刈り込まれる単語がなくても UNKNOWN_WORD を語彙に含める
+        let corpus = Corpus::new("a b a");
+        let pruned = corpus.prune_vocabulary(InitParamsOfVocabulary {
+            min_count: 1,
+            max_size: None,
+        });
+        assert_eq!(pruned.vocab_size(), 3);
+        assert_eq!(pruned.text, vec![1, 2, 1]);
+        assert_eq!(pruned.word_counts(), &[0, 2, 1]);
+    }
+
+    #[test]
+    fn test_word_to_id_or_unknown() {
+        let corpus = Corpus::new("a b a c");
+        assert_eq!(corpus.word_to_id_or_unknown("a"), Some(0));
+        assert_eq!(corpus.word_to_id_or_unknown("z"), None);
+
+        let pruned = corpus.prune_vocabulary(InitParamsOfVocabulary {
+            min_count: 2,
+            max_size: None,
+        });
+        assert_eq!(pruned.word_to_id_or_unknown("a"), Some(1));
+        assert_eq!(pruned.word_to_id_or_unknown("b"), Some(UNKNOWN_WORD_ID));
+        assert_eq!(pruned.word_to_id_or_unknown("z"), Some(UNKNOWN_WORD_ID));
+    }
+}
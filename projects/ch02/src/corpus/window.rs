@@ -0,0 +1,151 @@
+/*
+    共起を数えるウィンドウ
+
+    - direction: 左右両側・左側のみ・右側のみ
+    - weighting: 距離によらず 1、または距離 d に対して 1/d (GloVe)
+    - sentence_boundary: 指定した単語（"." など）をまたぐ共起は数えない
+      境界の単語自身は、それが終える文に属するものとする
+*/
+
+use super::{Corpus, Word, WordId};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum WindowDirection {
+    Both,
+    Left,
+    Right,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum WindowWeighting {
+    Uniform,
+    Harmonic,
+}
+
+#[derive(Clone)]
+pub struct Window {
+    pub window_size: usize,
+    pub direction: WindowDirection,
+    pub weighting: WindowWeighting,
+    pub sentence_boundary: Option<Word>,
+}
+
+impl Window {
+    // 左右 window_size 個以内の単語を重み 1 で数える（CoMatrix::new などの既定）
+    pub fn symmetric(window_size: usize) -> Self {
+        Self {
+            window_size,
+            direction: WindowDirection::Both,
+            weighting: WindowWeighting::Uniform,
+            sentence_boundary: None,
+        }
+    }
+
+    fn weight(&self, distance: usize) -> f32 {
+        match self.weighting {
+            WindowWeighting::Uniform => 1.,
+            WindowWeighting::Harmonic => 1. / distance as f32,
+        }
+    }
+}
+
+impl Corpus {
+    // 各単語と、そのウィンドウ内にある単語の組を重みとともに列挙する
+    pub(crate) fn co_occurrences<'a>(
+        &'a self,
+        window: &'a Window,
+    ) -> impl Iterator<Item = (WordId, WordId, f32)> + 'a {
+        let text = &self.text;
+        let boundary_id = window
+            .sentence_boundary
+            .as_deref()
+            .and_then(|word| self.word_to_id(word));
+        let is_boundary = move |word_id: WordId| Some(word_id) == boundary_id;
+
+        let use_left = window.direction != WindowDirection::Right;
+        let use_right = window.direction != WindowDirection::Left;
+
+        text.iter().enumerate().flat_map(move |(idx, &word_id)| {
+            // 境界の単語に達したら、それより遠くは見ない
+            let left = (1..=window.window_size)
+                .filter(move |_| use_left)
+                .take_while(move |&d| d <= idx && !is_boundary(text[idx - d]))
+                .map(move |d| (d, idx - d));
+            let right = (1..=window.window_size)
+                .filter(move |_| use_right)
+                .take_while(move |&d| idx + d < text.len() && !is_boundary(text[idx + d - 1]))
+                .map(move |d| (d, idx + d));
+
+            left.chain(right)
+                .map(move |(d, context_idx)| (word_id, text[context_idx], window.weight(d)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(corpus: &Corpus, window: &Window) -> Vec<(WordId, WordId, f32)> {
+        let mut co_occurrences = corpus.co_occurrences(window).collect::<Vec<_>>();
+        co_occurrences.sort_by(|x, y| x.partial_cmp(y).unwrap());
+        co_occurrences
+    }
+
+    #[test]
+    fn test_co_occurrences() {
+        // a: 0, b: 1, .: 2, c: 3
+        let corpus = Corpus::new("a b. c");
+
+        assert_eq!(
+            collect(&corpus, &Window::symmetric(1)),
+            vec![
+                (0, 1, 1.),
+                (1, 0, 1.),
+                (1, 2, 1.),
+                (2, 1, 1.),
+                (2, 3, 1.),
+                (3, 2, 1.)
+            ]
+        );
+
+        let window = Window {
+            window_size: 2,
+            direction: WindowDirection::Left,
+            weighting: WindowWeighting::Harmonic,
+            sentence_boundary: None,
+        };
+        assert_eq!(
+            collect(&corpus, &window),
+            vec![(1, 0, 1.), (2, 0, 0.5), (2, 1, 1.), (3, 1, 0.5), (3, 2, 1.)]
+        );
+
+        let window = Window {
+            direction: WindowDirection::Right,
+            ..window
+        };
+        assert_eq!(
+            collect(&corpus, &window),
+            vec![(0, 1, 1.), (0, 2, 0.5), (1, 2, 1.), (1, 3, 0.5), (2, 3, 1.)]
+        );
+
+        // "." をまたがない（"." は前の文に属する）
+        let window = Window {
+            window_size: 2,
+            direction: WindowDirection::Both,
+            weighting: WindowWeighting::Uniform,
+            sentence_boundary: Some(".".to_string()),
+        };
+        assert_eq!(
+            collect(&corpus, &window),
+            vec![
+                (0, 1, 1.),
+                (0, 2, 1.),
+                (1, 0, 1.),
+                (1, 2, 1.),
+                (2, 0, 1.),
+                (2, 1, 1.)
+            ]
+        );
+    }
+}
@@ -0,0 +1,2 @@
+pub mod word_analogy;
+pub mod word_similarity;
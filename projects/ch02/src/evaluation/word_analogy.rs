@@ -0,0 +1,242 @@
+/*
+    類推問題ベンチマーク (Google analogy test set) による評価
+
+    ファイル形式:
+    : capital-common-countries
+    Athens Greece Baghdad Iraq
+    ...
+
+    ":" で始まる行がセクションの見出し、それ以外の行が a b c d の４単語からなる問題
+    a : b = c : ? の答えの１位が d であれば正解とする
+*/
+
+use std::io::{self, BufRead};
+
+use crate::{
+    corpus::{Corpus, Word},
+    util::analogy::{analogy, AnalogyMethod},
+    word_matrix::WordMatrix,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalogyQuestion {
+    pub a: Word,
+    pub b: Word,
+    pub c: Word,
+    pub d: Word,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalogySection {
+    pub name: String,
+    pub questions: Vec<AnalogyQuestion>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectionResult {
+    pub name: String,
+    pub number_of_correct: usize,
+    // 評価に用いた問題の数
+    pub number_of_questions: usize,
+    // 語彙に含まれない単語があり、評価から除いた問題の数
+    pub number_of_oov_questions: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordAnalogyResult {
+    pub sections: Vec<SectionResult>,
+}
+
+// 最初の見出しより前の問題は、名前が空のセクションに入れる
+// lowercase: 小文字化したコーパス (DefaultTokenizer など) で評価する場合は true にして単語をそろえる
+pub fn load_analogy_questions<R: BufRead>(
+    reader: R,
+    lowercase: bool,
+) -> io::Result<Vec<AnalogySection>> {
+    let mut sections = Vec::<AnalogySection>::new();
+
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix(':') {
+            sections.push(AnalogySection {
+                name: name.trim().to_string(),
+                questions: vec![],
+            });
+            continue;
+        }
+
+        let words = line
+            .split_whitespace()
+            .map(|word| {
+                if lowercase {
+                    word.to_lowercase()
+                } else {
+                    word.to_string()
+                }
+            })
+            .collect::<Vec<_>>();
+        let [a, b, c, d]: [Word; 4] = words.try_into().map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("expected 4 words: {}", line),
+            )
+        })?;
+
+        if sections.is_empty() {
+            sections.push(AnalogySection {
+                name: String::new(),
+                questions: vec![],
+            });
+        }
+        sections
+            .last_mut()
+            .unwrap()
+            .questions
+            .push(AnalogyQuestion { a, b, c, d });
+    }
+
+    Ok(sections)
+}
+
+pub fn evaluate_word_analogy<T: WordMatrix>(
+    sections: &[AnalogySection],
+    corpus: &Corpus,
+    word_matrix: &T,
+    method: AnalogyMethod,
+) -> WordAnalogyResult {
+    let sections = sections
+        .iter()
+        .map(|section| {
+            let mut result = SectionResult {
+                name: section.name.clone(),
+                number_of_correct: 0,
+                number_of_questions: 0,
+                number_of_oov_questions: 0,
+            };
+
+            for AnalogyQuestion { a, b, c, d } in &section.questions {
+                if corpus.word_to_id(d).is_none() {
+                    result.number_of_oov_questions += 1;
+                    continue;
+                }
+                match analogy((a, b, c), corpus, word_matrix, 1, method) {
+                    Ok(answers) => {
+                        result.number_of_questions += 1;
+                        if answers.first().map(|(word, _)| word) == Some(d) {
+                            result.number_of_correct += 1;
+                        }
+                    }
+                    Err(_) => result.number_of_oov_questions += 1,
+                }
+            }
+
+            result
+        })
+        .collect();
+
+    WordAnalogyResult { sections }
+}
+
+impl SectionResult {
+    // 評価に用いた問題のうち正解した割合
+    pub fn accuracy(&self) -> f32 {
+        self.number_of_correct as f32 / self.number_of_questions as f32
+    }
+
+    // 全問題のうち評価に用いることができた割合
+    pub fn coverage(&self) -> f32 {
+        let total = self.number_of_questions + self.number_of_oov_questions;
+        self.number_of_questions as f32 / total as f32
+    }
+}
+
+impl WordAnalogyResult {
+    // 全セクションを合わせた結果
+    pub fn overall(&self) -> SectionResult {
+        SectionResult {
+            name: "overall".to_string(),
+            number_of_correct: self.sections.iter().map(|s| s.number_of_correct).sum(),
+            number_of_questions: self.sections.iter().map(|s| s.number_of_questions).sum(),
+            number_of_oov_questions: self
+                .sections
+                .iter()
+                .map(|s| s.number_of_oov_questions)
+                .sum(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn test_load_analogy_questions() {
+        let file = ": family\nMan Woman King Queen\n\n: gram\nman woman king\n";
+        assert!(load_analogy_questions(file.as_bytes(), true).is_err());
+
+        let file = ": family\nMan Woman King Queen\n\n: gram\nking queen man woman\n";
+        let sections = load_analogy_questions(file.as_bytes(), true).unwrap();
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].name, "family");
+        assert_eq!(
+            sections[0].questions,
+            vec![AnalogyQuestion {
+                a: "man".to_string(),
+                b: "woman".to_string(),
+                c: "king".to_string(),
+                d: "queen".to_string(),
+            }]
+        );
+        assert_eq!(sections[1].name, "gram");
+        assert_eq!(sections[1].questions.len(), 1);
+
+        // 大文字・小文字を区別するコーパス向けにはそのまま読む
+        let sections = load_analogy_questions(file.as_bytes(), false).unwrap();
+        assert_eq!(sections[0].questions[0].a, "Man");
+        assert_eq!(sections[1].questions[0].a, "king");
+    }
+
+    #[test]
+    fn test_evaluate_word_analogy() {
+        let corpus = Corpus::new("man woman king queen apple");
+        let word_matrix = array![
+            [1., 0., 0.],  // man
+            [1., 1., 0.],  // woman
+            [1., 0., 1.],  // king
+            [1., 1., 1.],  // queen
+            [0., 0., -1.], // apple
+        ];
+        let file = ": family\nman woman king queen\nman king woman queen\nman woman king apple\n\
+                    : unknown\nman woman prince princess\n";
+        let sections = load_analogy_questions(file.as_bytes(), true).unwrap();
+
+        for method in [AnalogyMethod::CosAdd, AnalogyMethod::CosMul] {
+            let result = evaluate_word_analogy(&sections, &corpus, &word_matrix, method);
+
+            let family = &result.sections[0];
+            assert_eq!(family.number_of_correct, 2);
+            assert_eq!(family.number_of_questions, 3);
+            assert_eq!(family.number_of_oov_questions, 0);
+            assert_abs_diff_eq!(family.accuracy(), 2. / 3.);
+
+            let unknown = &result.sections[1];
+            assert_eq!(unknown.number_of_questions, 0);
+            assert_eq!(unknown.number_of_oov_questions, 1);
+            assert_eq!(unknown.coverage(), 0.);
+
+            let overall = result.overall();
+            assert_eq!(overall.number_of_correct, 2);
+            assert_abs_diff_eq!(overall.accuracy(), 2. / 3.);
+            assert_abs_diff_eq!(overall.coverage(), 3. / 4.);
+        }
+    }
+}
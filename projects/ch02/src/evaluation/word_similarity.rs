@@ -0,0 +1,218 @@
+/*
+    単語類似度ベンチマーク (WordSim353, SimLex-999 など) による評価
+
+    各行が「単語1 <TAB> 単語2 <TAB> 人手による類似度」のファイルを読み込み、
+    単語ベクトルのコサイン類似度と人手のスコアとのスピアマンの順位相関係数を求める
+*/
+
+use std::io::{self, BufRead};
+
+use crate::{
+    corpus::{Corpus, Word},
+    util::cos_similarity::cos_similarity,
+    word_matrix::WordMatrix,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimilarityPair {
+    pub word1: Word,
+    pub word2: Word,
+    pub score: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordSimilarityResult {
+    pub spearman: f32,
+    // 評価に用いた単語対の数
+    pub number_of_pairs: usize,
+    // どちらかの単語が語彙に含まれず、評価から除いた単語対の数
+    pub number_of_oov_pairs: usize,
+}
+
+// 空行・'#' で始まる行・スコアが数値でない行（ヘッダ行）は読み飛ばす
+// ４列目以降（SimLex-999 の POS など）は無視する
+// lowercase: 小文字化したコーパス (DefaultTokenizer など) で評価する場合は true にして単語をそろえる
+pub fn load_similarity_pairs<R: BufRead>(
+    reader: R,
+    lowercase: bool,
+) -> io::Result<Vec<SimilarityPair>> {
+    let mut pairs = vec![];
+
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let columns = line.split('\t').collect::<Vec<_>>();
+        if columns.len() < 3 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("expected 3 tab-separated columns: {}", line),
+            ));
+        }
+
+        let score = match columns[2].trim().parse::<f32>() {
+            Ok(score) => score,
+            Err(_) => continue,
+        };
+        let word = |column: &str| {
+            if lowercase {
+                column.trim().to_lowercase()
+            } else {
+                column.trim().to_string()
+            }
+        };
+        pairs.push(SimilarityPair {
+            word1: word(columns[0]),
+            word2: word(columns[1]),
+            score,
+        });
+    }
+
+    Ok(pairs)
+}
+
+pub fn evaluate_word_similarity<T: WordMatrix>(
+    pairs: &[SimilarityPair],
+    corpus: &Corpus,
+    word_matrix: &T,
+) -> WordSimilarityResult {
+    let mut human_scores = vec![];
+    let mut model_scores = vec![];
+    let mut number_of_oov_pairs = 0;
+
+    for pair in pairs {
+        match (
+            corpus.word_to_id(&pair.word1),
+            corpus.word_to_id(&pair.word2),
+        ) {
+            (Some(id1), Some(id2)) => {
+                human_scores.push(pair.score);
+                model_scores.push(cos_similarity(
+                    word_matrix.row(id1).view(),
+                    word_matrix.row(id2).view(),
+                    None,
+                ));
+            }
+            _ => number_of_oov_pairs += 1,
+        }
+    }
+
+    WordSimilarityResult {
+        spearman: spearman(&human_scores, &model_scores),
+        number_of_pairs: human_scores.len(),
+        number_of_oov_pairs,
+    }
+}
+
+// 順位のピアソン相関係数として計算する
+// 評価できる単語対が２つ未満の場合は NaN
+fn spearman(x: &[f32], y: &[f32]) -> f32 {
+    assert_eq!(x.len(), y.len());
+    if x.len() < 2 {
+        return f32::NAN;
+    }
+
+    let (rank_x, rank_y) = (rank(x), rank(y));
+    let mean_x = rank_x.iter().sum::<f32>() / rank_x.len() as f32;
+    let mean_y = rank_y.iter().sum::<f32>() / rank_y.len() as f32;
+
+    let (mut cov, mut var_x, mut var_y) = (0., 0., 0.);
+    for (rx, ry) in rank_x.iter().zip(&rank_y) {
+        cov += (rx - mean_x) * (ry - mean_y);
+        var_x += (rx - mean_x).powi(2);
+        var_y += (ry - mean_y).powi(2);
+    }
+    cov / (var_x * var_y).sqrt()
+}
+
+// 同順位の値には平均順位を与える（順位は１始まり）
+fn rank(values: &[f32]) -> Vec<f32> {
+    let mut indices = (0..values.len()).collect::<Vec<_>>();
+    indices.sort_by(|&i, &j| values[i].partial_cmp(&values[j]).unwrap());
+
+    let mut ranks = vec![0.; values.len()];
+    let mut start = 0;
+    while start < indices.len() {
+        let mut end = start + 1;
+        while end < indices.len() && values[indices[end]] == values[indices[start]] {
+            end += 1;
+        }
+        let average_rank = (start + end + 1) as f32 / 2.;
+        for &i in &indices[start..end] {
+            ranks[i] = average_rank;
+        }
+        start = end;
+    }
+    ranks
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn test_spearman() {
+        assert_eq!(rank(&[10., 30., 20., 30.]), vec![1., 3.5, 2., 3.5]);
+
+        // 単調増加なら１、単調減少なら -1
+        assert_abs_diff_eq!(spearman(&[1., 2., 3., 4.], &[1., 4., 9., 16.]), 1.);
+        assert_abs_diff_eq!(spearman(&[1., 2., 3., 4.], &[8., 4., 2., 1.]), -1.);
+
+        // 1 - 6 Σd^2 / (n (n^2 - 1)) = 1 - 6 * 2 / (4 * 15)
+        assert_abs_diff_eq!(spearman(&[1., 2., 3., 4.], &[1., 3., 2., 4.]), 0.8);
+
+        assert!(spearman(&[1.], &[1.]).is_nan());
+    }
+
+    #[test]
+    fn test_load_similarity_pairs() {
+        let file =
+            "Word 1\tWord 2\tHuman (mean)\n# comment\n\nTiger\tcat\t7.35\nbook\tpaper\t7.46\tN\n";
+        let pairs = load_similarity_pairs(file.as_bytes(), true).unwrap();
+        assert_eq!(
+            pairs,
+            vec![
+                SimilarityPair {
+                    word1: "tiger".to_string(),
+                    word2: "cat".to_string(),
+                    score: 7.35,
+                },
+                SimilarityPair {
+                    word1: "book".to_string(),
+                    word2: "paper".to_string(),
+                    score: 7.46,
+                },
+            ]
+        );
+
+        // 大文字・小文字を区別するコーパス向けにはそのまま読む
+        let pairs = load_similarity_pairs(file.as_bytes(), false).unwrap();
+        assert_eq!(pairs[0].word1, "Tiger");
+
+        assert!(load_similarity_pairs("tiger cat 7.35\n".as_bytes(), true).is_err());
+    }
+
+    #[test]
+    fn test_evaluate_word_similarity() {
+        let corpus = Corpus::new("cat tiger car truck");
+        let word_matrix = array![
+            [1., 0.1], // cat
+            [1., 0.3], // tiger
+            [0.1, 1.], // car
+            [0.2, 1.], // truck
+        ];
+        let file = "cat\ttiger\t8\ncar\ttruck\t9\ncat\tcar\t1\ntiger\ttruck\t2\ncat\tdog\t8\n";
+        let pairs = load_similarity_pairs(file.as_bytes(), true).unwrap();
+
+        let result = evaluate_word_similarity(&pairs, &corpus, &word_matrix);
+        assert_eq!(result.number_of_pairs, 4);
+        assert_eq!(result.number_of_oov_pairs, 1);
+        assert_abs_diff_eq!(result.spearman, 1.);
+    }
+}
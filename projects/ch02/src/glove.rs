@@ -0,0 +1,196 @@
+/*
+    GloVe (Pennington et al., 2014)
+
+    共起回数 X_ij が非ゼロの組について、次の重み付き最小二乗誤差を最小化する
+    J = Σ_{i, j} f(X_ij) (w_i・w̃_j + b_i + b̃_j - log X_ij)^2
+    f(x) = (x / x_max)^alpha  (x < x_max), 1  (otherwise)
+
+    パラメータは AdaGrad で更新し、単語ベクトルとしては W + W̃ を用いる
+*/
+
+use ndarray::{Array, Array1, Array2, CowArray, Dimension, Ix1};
+use ndarray_rand::{rand_distr::Uniform, RandomExt};
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    corpus::WordId,
+    word_matrix::{csr_matrix::CsrMatrix, WordMatrix},
+};
+
+pub struct Glove {
+    // 共起回数が非ゼロの (i, j, X_ij)
+    co_occurrences: Vec<(WordId, WordId, f32)>,
+    x_max: f32,
+    alpha: f32,
+    w: AdaGrad<Array2<f32>>,
+    w_tilde: AdaGrad<Array2<f32>>,
+    b: AdaGrad<Array1<f32>>,
+    b_tilde: AdaGrad<Array1<f32>>,
+}
+
+pub struct InitParamsOfGlove {
+    pub dim: usize,
+    // 論文では x_max = 100, alpha = 0.75
+    pub x_max: f32,
+    pub alpha: f32,
+    pub learning_rate: f32,
+}
+
+// パラメータと、その勾配の二乗和
+// h は 1 で初期化する（GloVe の参照実装と同じ）
+struct AdaGrad<A> {
+    params: A,
+    h: A,
+    learning_rate: f32,
+}
+
+impl Glove {
+    pub fn new<R: Rng>(co_matrix: &CsrMatrix, params: InitParamsOfGlove, rng: &mut R) -> Self {
+        let InitParamsOfGlove {
+            dim,
+            x_max,
+            alpha,
+            learning_rate,
+        } = params;
+
+        let (vocab_size, number_of_contexts) = co_matrix.dim();
+        assert_eq!(vocab_size, number_of_contexts);
+
+        let co_occurrences = co_matrix.iter().filter(|&(_, _, x)| x > 0.).collect();
+
+        let range = Uniform::new(-0.5 / dim as f32, 0.5 / dim as f32);
+        let w = Array2::random_using((vocab_size, dim), range, rng);
+        let w_tilde = Array2::random_using((vocab_size, dim), range, rng);
+
+        Self {
+            co_occurrences,
+            x_max,
+            alpha,
+            w: AdaGrad::new(w, learning_rate),
+            w_tilde: AdaGrad::new(w_tilde, learning_rate),
+            b: AdaGrad::new(Array1::zeros(vocab_size), learning_rate),
+            b_tilde: AdaGrad::new(Array1::zeros(vocab_size), learning_rate),
+        }
+    }
+
+    // 非ゼロの共起をシャッフルして１回ずつ学習し、平均の損失を返す
+    pub fn train_epoch<R: Rng>(&mut self, rng: &mut R) -> f32 {
+        self.co_occurrences.shuffle(rng);
+        let mut total_loss = 0.;
+
+        for idx in 0..self.co_occurrences.len() {
+            let (i, j, x) = self.co_occurrences[idx];
+            let weight = if x < self.x_max {
+                (x / self.x_max).powf(self.alpha)
+            } else {
+                1.
+            };
+
+            let w_i = self.w.params.row(i);
+            let w_tilde_j = self.w_tilde.params.row(j);
+            let diff = w_i.dot(&w_tilde_j) + self.b.params[i] + self.b_tilde.params[j] - x.ln();
+            total_loss += 0.5 * weight * diff * diff;
+
+            // J の 1/2 倍を微分する
+            let dw_i = &w_tilde_j * (weight * diff);
+            let dw_tilde_j = &w_i * (weight * diff);
+            let db = weight * diff;
+
+            self.w.update_row(i, &dw_i);
+            self.w_tilde.update_row(j, &dw_tilde_j);
+            self.b.update_element(i, db);
+            self.b_tilde.update_element(j, db);
+        }
+
+        total_loss / self.co_occurrences.len() as f32
+    }
+
+    pub fn word_vectors(&self) -> Array2<f32> {
+        &self.w.params + &self.w_tilde.params
+    }
+}
+
+impl<D: Dimension> AdaGrad<Array<f32, D>> {
+    fn new(params: Array<f32, D>, learning_rate: f32) -> Self {
+        let h = Array::ones(params.raw_dim());
+        Self {
+            params,
+            h,
+            learning_rate,
+        }
+    }
+}
+
+// 単語ごとに更新するので、行・要素単位で更新する
+impl AdaGrad<Array2<f32>> {
+    fn update_row(&mut self, i: usize, grad: &Array1<f32>) {
+        let mut h = self.h.row_mut(i);
+        h += &(grad * grad);
+        let step = grad * self.learning_rate / &h.mapv(f32::sqrt);
+        let mut params = self.params.row_mut(i);
+        params -= &step;
+    }
+}
+
+impl AdaGrad<Array1<f32>> {
+    fn update_element(&mut self, i: usize, grad: f32) {
+        self.h[i] += grad * grad;
+        self.params[i] -= self.learning_rate * grad / self.h[i].sqrt();
+    }
+}
+
+impl WordMatrix for Glove {
+    fn shape(&self) -> (usize, usize) {
+        self.w.params.dim()
+    }
+
+    fn row(&self, word_id: WordId) -> CowArray<'_, f32, Ix1> {
+        CowArray::from(&self.w.params.row(word_id) + &self.w_tilde.params.row(word_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{
+        corpus::{window::Window, Corpus},
+        word_matrix::sparse_co_matrix::SparseCoMatrix,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_glove() {
+        let text = "You say goodbye and I say hello.";
+        let corpus = Corpus::new(text);
+        let co_matrix = SparseCoMatrix::with_window(&corpus, &Window::symmetric(2));
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let mut glove = Glove::new(
+            co_matrix.matrix(),
+            InitParamsOfGlove {
+                dim: 5,
+                // 共起回数が小さいので、すべての組を重み 1 で学習する
+                x_max: 1.,
+                alpha: 0.75,
+                learning_rate: 0.1,
+            },
+            &mut rng,
+        );
+        assert_eq!(glove.shape(), (7, 5));
+
+        let first_loss = glove.train_epoch(&mut rng);
+        let last_loss = (0..500)
+            .map(|_| glove.train_epoch(&mut rng))
+            .last()
+            .unwrap();
+        assert!(last_loss < first_loss * 1e-3);
+
+        // W + W̃ の各行を WordMatrix として取り出せる
+        let word_vectors = glove.word_vectors();
+        for word_id in 0..7 {
+            assert_eq!(glove.row(word_id), word_vectors.row(word_id));
+        }
+    }
+}
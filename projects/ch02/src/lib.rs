@@ -1,4 +1,11 @@
 pub mod corpus;
+pub mod evaluation;
+pub mod glove;
+pub mod similarity_index;
 pub mod util;
+pub mod visualization;
 
 pub mod word_matrix;
+
+pub mod negative_sampling;
+pub mod word2vec_format;
@@ -0,0 +1,197 @@
+/*
+    正例（ターゲット）については「正解である」、
+    UnigramSampler で選んだ負例については「正解でない」という二値分類を行い、
+    その損失の和を取る
+
+    L = L_positive + Σ_{i: 負例} L_negative_i
+*/
+
+use ndarray::{Array1, Array2};
+use rand::Rng;
+
+use crate::corpus::{Corpus, WordId};
+
+use self::{
+    embedding_dot::EmbeddingDot, sigmoid_with_loss::SigmoidWithLoss,
+    unigram_sampler::UnigramSampler,
+};
+
+pub(crate) mod embedding_dot;
+pub(crate) mod sigmoid_with_loss;
+pub mod unigram_sampler;
+
+pub struct NegativeSamplingLoss {
+    sampler: UnigramSampler,
+    // 先頭が正例用、残り sample_size 個が負例用
+    embedding_dot_layers: Vec<EmbeddingDot>,
+    loss_layers: Vec<SigmoidWithLoss>,
+    params: Array2<f32>,
+    grads: Array2<f32>,
+}
+
+pub struct InitParamsOfNegativeSamplingLoss {
+    pub w: Array2<f32>,
+    pub power: f32,
+    pub sample_size: usize,
+}
+
+impl NegativeSamplingLoss {
+    pub fn new(corpus: &Corpus, params: InitParamsOfNegativeSamplingLoss) -> Self {
+        let InitParamsOfNegativeSamplingLoss {
+            w,
+            power,
+            sample_size,
+        } = params;
+
+        assert_eq!(w.dim().0, corpus.id_to_word.len());
+
+        let sampler = UnigramSampler::new(corpus, power, sample_size);
+        let embedding_dot_layers = (0..=sample_size).map(|_| EmbeddingDot::new()).collect();
+        let loss_layers = (0..=sample_size).map(|_| SigmoidWithLoss::new()).collect();
+        let grads = Array2::zeros(w.dim());
+
+        Self {
+            sampler,
+            embedding_dot_layers,
+            loss_layers,
+            params: w,
+            grads,
+        }
+    }
+
+    pub fn forward<R: Rng>(&mut self, h: Array2<f32>, target: &[WordId], rng: &mut R) -> f32 {
+        let negative_sample = self.sampler.get_negative_sample(target, rng);
+        self.forward_with_negative_sample(h, target, &negative_sample)
+    }
+
+    fn forward_with_negative_sample(
+        &mut self,
+        h: Array2<f32>,
+        target: &[WordId],
+        negative_sample: &Array2<WordId>,
+    ) -> f32 {
+        assert_eq!(h.dim().0, target.len());
+        assert_eq!(
+            negative_sample.dim(),
+            (target.len(), self.sampler.sample_size())
+        );
+
+        let batch_size = target.len();
+
+        // 正例
+        let score = self.embedding_dot_layers[0].forward(&self.params, &h, target);
+        let mut loss = self.loss_layers[0].forward(score, Array1::ones(batch_size));
+
+        // 負例
+        for (i, negative_target) in negative_sample.columns().into_iter().enumerate() {
+            let negative_target = negative_target.to_vec();
+            let score =
+                self.embedding_dot_layers[i + 1].forward(&self.params, &h, &negative_target);
+            loss += self.loss_layers[i + 1].forward(score, Array1::zeros(batch_size));
+        }
+
+        loss
+    }
+
+    pub fn backward(&mut self, dout: f32) -> Array2<f32> {
+        self.grads.fill(0.);
+
+        let mut dh: Option<Array2<f32>> = None;
+        for (embedding_dot, loss_layer) in self
+            .embedding_dot_layers
+            .iter()
+            .zip(self.loss_layers.iter())
+        {
+            let dscore = loss_layer.backward(dout);
+            let dh_i = embedding_dot.backward(&dscore, &mut self.grads);
+            dh = Some(match dh {
+                Some(dh) => dh + dh_i,
+                None => dh_i,
+            });
+        }
+
+        dh.unwrap()
+    }
+
+    pub fn params_and_grads(&mut self) -> (&mut Array2<f32>, &Array2<f32>) {
+        (&mut self.params, &self.grads)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn test_negative_sampling_loss_backward() {
+        let text = "You say goodbye and I say hello.";
+        let corpus = Corpus::new(text);
+
+        let w = array![
+            [0.1, -0.2, 0.3],
+            [0.4, 0.5, -0.6],
+            [-0.7, 0.8, 0.9],
+            [1.0, -1.1, 1.2],
+            [-1.3, 1.4, -1.5],
+            [1.6, 1.7, 1.8],
+            [-1.9, -2.0, 2.1],
+        ];
+        let h = array![[0.5, -0.3, 0.2], [-0.1, 0.4, 0.6]];
+        let target = [1, 5];
+        // 乱数に依存しないよう負例を固定する
+        let negative_sample = array![[0, 2], [6, 3]];
+
+        let mut loss_layer = NegativeSamplingLoss::new(
+            &corpus,
+            InitParamsOfNegativeSamplingLoss {
+                w: w.clone(),
+                power: 0.75,
+                sample_size: 2,
+            },
+        );
+        loss_layer.forward_with_negative_sample(h.clone(), &target, &negative_sample);
+        let dh = loss_layer.backward(1.);
+        let (_, dw) = loss_layer.params_and_grads();
+        let dw = dw.clone();
+
+        // 微分を数値計算するための微小量
+        const DELTA: f32 = 1e-3;
+
+        let loss_of = |w: Array2<f32>, h: Array2<f32>| {
+            let mut loss_layer = NegativeSamplingLoss::new(
+                &corpus,
+                InitParamsOfNegativeSamplingLoss {
+                    w,
+                    power: 0.75,
+                    sample_size: 2,
+                },
+            );
+            loss_layer.forward_with_negative_sample(h, &target, &negative_sample)
+        };
+
+        // h の [i, j] 成分に関する微分を数値計算
+        for ((i, j), &dh_ij) in dh.indexed_iter() {
+            let mut h_plus = h.clone();
+            h_plus[[i, j]] += DELTA;
+            let mut h_minus = h.clone();
+            h_minus[[i, j]] -= DELTA;
+            let expected =
+                (loss_of(w.clone(), h_plus) - loss_of(w.clone(), h_minus)) / (2. * DELTA);
+            assert_abs_diff_eq!(dh_ij, expected, epsilon = 1e-2);
+        }
+
+        // w の [i, j] 成分に関する微分を数値計算
+        for ((i, j), &dw_ij) in dw.indexed_iter() {
+            let mut w_plus = w.clone();
+            w_plus[[i, j]] += DELTA;
+            let mut w_minus = w.clone();
+            w_minus[[i, j]] -= DELTA;
+            let expected =
+                (loss_of(w_plus, h.clone()) - loss_of(w_minus, h.clone())) / (2. * DELTA);
+            assert_abs_diff_eq!(dw_ij, expected, epsilon = 1e-2);
+        }
+    }
+}
@@ -0,0 +1,87 @@
+/*
+    W_idx = W[idx]  (埋め込み行列 W から idx 番目の行を取り出したもの)
+    y_n = Σ_{k} W_idx[n, k] * H[n, k]
+
+    ∂L/∂H[n, k] = ∂L/∂y_n * W_idx[n, k]
+    ∂L/∂W[idx[n], k] += ∂L/∂y_n * H[n, k]
+*/
+
+use ndarray::{Array1, Array2, Axis};
+
+use crate::corpus::WordId;
+
+pub(crate) struct EmbeddingDot {
+    h: Option<Array2<f32>>,
+    idx: Option<Vec<WordId>>,
+    target_w: Option<Array2<f32>>,
+}
+
+impl EmbeddingDot {
+    pub(crate) fn new() -> Self {
+        Self {
+            h: None,
+            idx: None,
+            target_w: None,
+        }
+    }
+
+    pub(crate) fn forward(
+        &mut self,
+        w: &Array2<f32>,
+        h: &Array2<f32>,
+        idx: &[WordId],
+    ) -> Array1<f32> {
+        let target_w = w.select(Axis(0), idx);
+        let out = (&target_w * h).sum_axis(Axis(1));
+
+        self.h = Some(h.clone());
+        self.idx = Some(idx.to_vec());
+        self.target_w = Some(target_w);
+
+        out
+    }
+
+    // 埋め込み行列の勾配は dw に加算し、h の勾配を返す
+    pub(crate) fn backward(&self, dout: &Array1<f32>, dw: &mut Array2<f32>) -> Array2<f32> {
+        assert!(self.h.is_some());
+        assert!(self.idx.is_some());
+        assert!(self.target_w.is_some());
+        let h = self.h.as_ref().unwrap();
+        let idx = self.idx.as_ref().unwrap();
+        let target_w = self.target_w.as_ref().unwrap();
+
+        let dout = dout.view().insert_axis(Axis(1));
+
+        let dtarget_w = &dout * h;
+        for (dtarget_w_row, &word_id) in dtarget_w.rows().into_iter().zip(idx) {
+            let mut dw_row = dw.row_mut(word_id);
+            dw_row += &dtarget_w_row;
+        }
+
+        &dout * target_w
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn test_embedding_dot() {
+        // test forward
+        let mut embedding_dot = EmbeddingDot::new();
+        let w = array![[1., 2.], [3., 4.], [5., 6.], [7., 8.]];
+        let h = array![[1., 1.], [2., -1.], [0., 3.]];
+        let out = embedding_dot.forward(&w, &h, &[3, 0, 3]);
+        assert_eq!(out, array![15., 0., 24.]);
+
+        // test backward
+        let mut dw = Array2::zeros(w.dim());
+        let dh = embedding_dot.backward(&array![1., 2., 3.], &mut dw);
+        assert_eq!(dh, array![[7., 8.], [2., 4.], [21., 24.]]);
+        // 同じ単語が複数回選ばれた場合は勾配が加算される
+        assert_eq!(dw, array![[4., -2.], [0., 0.], [0., 0.], [1., 10.]]);
+    }
+}
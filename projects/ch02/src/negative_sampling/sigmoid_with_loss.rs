@@ -0,0 +1,83 @@
+/*
+    y_n = 1 / (1 + exp(-x_n))
+    L = (1 / N) Σ_{n} -(t_n ln(y_n) + (1 - t_n) ln(1 - y_n))
+
+    ∂L/∂x_n = (y_n - t_n) / N
+*/
+
+use ndarray::Array1;
+
+const TINY_DELTA: f32 = 1e-7;
+
+pub(crate) struct SigmoidWithLoss {
+    y: Option<Array1<f32>>,
+    t: Option<Array1<f32>>,
+}
+
+impl SigmoidWithLoss {
+    pub(crate) fn new() -> Self {
+        Self { y: None, t: None }
+    }
+
+    pub(crate) fn forward(&mut self, input: Array1<f32>, t: Array1<f32>) -> f32 {
+        assert_eq!(input.len(), t.len());
+        let batch_size = input.len() as f32;
+
+        let y = input.mapv_into(|x| 1. / (1. + (-x).exp()));
+        let loss = y
+            .iter()
+            .zip(&t)
+            .map(|(&y, &t)| -(t * (y + TINY_DELTA).ln() + (1. - t) * (1. - y + TINY_DELTA).ln()))
+            .sum::<f32>()
+            / batch_size;
+
+        self.y = Some(y);
+        self.t = Some(t);
+
+        loss
+    }
+
+    pub(crate) fn backward(&self, dout: f32) -> Array1<f32> {
+        assert!(self.y.is_some());
+        assert!(self.t.is_some());
+        let y = self.y.as_ref().unwrap();
+        let t = self.t.as_ref().unwrap();
+
+        let batch_size = y.len() as f32;
+        (y - t) * dout / batch_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn test_sigmoid_with_loss() {
+        // test forward
+        let mut sigmoid_with_loss = SigmoidWithLoss::new();
+        let loss = sigmoid_with_loss.forward(array![2., -1., 0.5], array![1., 0., 0.]);
+
+        let sigmoid = |x: f32| 1. / (1. + (-x).exp());
+        let expected =
+            -(sigmoid(2.).ln() + (1. - sigmoid(-1.)).ln() + (1. - sigmoid(0.5)).ln()) / 3.;
+        assert_abs_diff_eq!(loss, expected, epsilon = 1e-5);
+
+        // test backward
+        let dinput = sigmoid_with_loss.backward(1.);
+        let expected = array![
+            (sigmoid(2.) - 1.) / 3.,
+            sigmoid(-1.) / 3.,
+            sigmoid(0.5) / 3.
+        ];
+        dinput
+            .iter()
+            .zip(expected.iter())
+            .for_each(|(actual, expected)| {
+                assert_abs_diff_eq!(actual, expected);
+            });
+    }
+}
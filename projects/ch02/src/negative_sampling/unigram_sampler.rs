@@ -0,0 +1,152 @@
+/*
+    P'(w_i) = P(w_i)^power / Σ_{j} P(w_j)^power
+
+    power (< 1) で確率を均すことで、出現頻度の低い単語も負例として選ばれやすくする
+    （word2vec では power = 0.75）
+
+    保存した単語ベクトルから読み込んだコーパスのように出現回数がすべて０の場合は、一様分布とする
+*/
+
+use ndarray::Array2;
+use rand::{distributions::WeightedIndex, prelude::Distribution, Rng};
+
+use crate::corpus::{Corpus, WordId};
+
+pub struct UnigramSampler {
+    sample_size: usize,
+    word_p: Vec<f32>,
+    distribution: WeightedIndex<f32>,
+}
+
+impl UnigramSampler {
+    pub fn new(corpus: &Corpus, power: f32, sample_size: usize) -> Self {
+        // 出現回数を power 乗して正規化
+        let word_p = corpus
+            .word_counts()
+            .iter()
+            .map(|&count| (count as f32).powf(power))
+            .collect::<Vec<_>>();
+        let total: f32 = word_p.iter().sum();
+        let word_p = if total > 0. {
+            word_p.into_iter().map(|p| p / total).collect::<Vec<_>>()
+        } else {
+            vec![1. / word_p.len() as f32; word_p.len()]
+        };
+
+        // 正例を除いても、確率が正の単語から sample_size 個の異なる単語を選べることを要請
+        // （満たさないと get_negative_sample が終わらない）
+        let number_of_candidates = word_p.iter().filter(|&&p| p > 0.).count();
+        assert!(
+            sample_size < number_of_candidates,
+            "sample_size ({}) must be less than the number of words with positive probability ({})",
+            sample_size,
+            number_of_candidates
+        );
+
+        let distribution = WeightedIndex::new(&word_p).unwrap();
+
+        Self {
+            sample_size,
+            word_p,
+            distribution,
+        }
+    }
+
+    pub fn sample_size(&self) -> usize {
+        self.sample_size
+    }
+
+    pub fn word_p(&self) -> &[f32] {
+        &self.word_p
+    }
+
+    // 各ターゲットについて、ターゲット自身を含まない重複のない負例を sample_size 個ずつ選ぶ
+    pub fn get_negative_sample<R: Rng>(&self, target: &[WordId], rng: &mut R) -> Array2<WordId> {
+        let mut negative_sample = Array2::<WordId>::zeros((target.len(), self.sample_size));
+
+        for (mut row, &target_id) in negative_sample.rows_mut().into_iter().zip(target) {
+            let mut sampled = Vec::with_capacity(self.sample_size);
+            while sampled.len() < self.sample_size {
+                let word_id = self.distribution.sample(rng);
+                if word_id != target_id && !sampled.contains(&word_id) {
+                    sampled.push(word_id);
+                }
+            }
+            row.iter_mut()
+                .zip(sampled)
+                .for_each(|(element, word_id)| *element = word_id);
+        }
+
+        negative_sample
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn test_word_p() {
+        let text = "You say goodbye and I say hello.";
+        let corpus = Corpus::new(text);
+        let sampler = UnigramSampler::new(&corpus, 0.75, 2);
+
+        // say だけが２回出現する
+        let total = 6. + 2_f32.powf(0.75);
+        let expected = [
+            1. / total,
+            2_f32.powf(0.75) / total,
+            1. / total,
+            1. / total,
+            1. / total,
+            1. / total,
+            1. / total,
+        ];
+        sampler
+            .word_p()
+            .iter()
+            .zip(expected)
+            .for_each(|(&actual, expected)| {
+                assert_abs_diff_eq!(actual, expected);
+            });
+    }
+
+    #[test]
+    fn test_get_negative_sample() {
+        let text = "You say goodbye and I say hello.";
+        let corpus = Corpus::new(text);
+        let sampler = UnigramSampler::new(&corpus, 0.75, 5);
+
+        let target = [1, 3, 0, 6];
+        let negative_sample = sampler.get_negative_sample(&target, &mut StdRng::seed_from_u64(0));
+        assert_eq!(negative_sample.dim(), (4, 5));
+
+        for (row, target_id) in negative_sample.rows().into_iter().zip(target) {
+            // 正例が含まれていないこと
+            assert!(row.iter().all(|&word_id| word_id != target_id));
+
+            // 重複がないこと
+            let mut row = row.to_vec();
+            row.sort();
+            row.dedup();
+            assert_eq!(row.len(), 5);
+        }
+    }
+
+    #[test]
+    fn test_zero_counts() {
+        // 語彙だけを持つコーパスでは一様分布になる
+        let corpus = Corpus::from_vocabulary(["a", "b", "c"].map(String::from));
+        let sampler = UnigramSampler::new(&corpus, 0.75, 2);
+        assert_eq!(sampler.word_p(), &[1. / 3.; 3]);
+
+        // 正例以外の残りの２単語がちょうど選ばれる
+        let negative_sample = sampler.get_negative_sample(&[1], &mut StdRng::seed_from_u64(0));
+        let mut row = negative_sample.row(0).to_vec();
+        row.sort();
+        assert_eq!(row, vec![0, 2]);
+    }
+}
@@ -0,0 +1,303 @@
+/*
+    コサイン類似度による近傍探索
+
+    単語行列は元の形式（疎行列なら疎行列）のまま持ち、各行のノルムだけをあらかじめ計算しておく
+    問い合わせベクトルとの内積を行列積でまとめて計算し、ノルムで割ってコサイン類似度とする
+    上位 top 個はサイズ top のヒープで選ぶので、語彙全体をソートしない
+
+    with_lsh で作ると、search_approximate で LSH による近似探索も行える
+*/
+
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2, Axis};
+use rand::Rng;
+
+use crate::{corpus::WordId, word_matrix::WordMatrix};
+
+use self::lsh::{InitParamsOfLsh, Lsh};
+
+pub mod lsh;
+
+const EPSILON: f32 = 1e-8;
+
+pub struct SimilarityIndex<T> {
+    word_matrix: T,
+    // 各行のノルム
+    norms: Array1<f32>,
+    lsh: Option<Lsh>,
+}
+
+impl<T: WordMatrix> SimilarityIndex<T> {
+    pub fn new(word_matrix: T) -> Self {
+        let norms = word_matrix.row_norms();
+        Self {
+            word_matrix,
+            norms,
+            lsh: None,
+        }
+    }
+
+    pub fn with_lsh<R: Rng>(word_matrix: T, params: InitParamsOfLsh, rng: &mut R) -> Self {
+        let mut index = Self::new(word_matrix);
+        index.lsh = Some(Lsh::new(&index.word_matrix, params, rng));
+        index
+    }
+
+    pub fn word_matrix(&self) -> &T {
+        &self.word_matrix
+    }
+
+    pub fn vocab_size(&self) -> usize {
+        self.norms.len()
+    }
+
+    pub fn norms(&self) -> ArrayView1<'_, f32> {
+        self.norms.view()
+    }
+
+    // 全単語と query_ids の各単語との内積 (語彙数, 問い合わせ数)
+    pub fn dots(&self, query_ids: &[WordId]) -> Array2<f32> {
+        let (_, dim) = self.word_matrix.shape();
+        // (次元, 問い合わせ数)
+        let mut queries = Array2::zeros((dim, query_ids.len()));
+        for (mut column, &query_id) in queries.columns_mut().into_iter().zip(query_ids) {
+            column.assign(&self.word_matrix.row(query_id));
+        }
+        self.word_matrix.matmul(&queries)
+    }
+
+    // 全単語と query_ids の各単語とのコサイン類似度 (語彙数, 問い合わせ数)
+    pub fn cos_similarities(&self, query_ids: &[WordId]) -> Array2<f32> {
+        let query_norms = query_ids
+            .iter()
+            .map(|&query_id| self.norms[query_id])
+            .collect::<Array1<f32>>();
+        self.to_cos_similarities(self.dots(query_ids), &query_norms)
+    }
+
+    // exclude に含まれる単語を除いて、query とのコサイン類似度が大きい順に top 個を返す
+    pub fn search(
+        &self,
+        query: ArrayView1<f32>,
+        top: usize,
+        exclude: &[WordId],
+    ) -> Vec<(WordId, f32)> {
+        let mut results = self.search_batch(query.insert_axis(Axis(0)), top, exclude);
+        results.pop().unwrap()
+    }
+
+    // 複数の問い合わせ（各行）をまとめて１回の行列積で計算する
+    pub fn search_batch(
+        &self,
+        queries: ArrayView2<f32>,
+        top: usize,
+        exclude: &[WordId],
+    ) -> Vec<Vec<(WordId, f32)>> {
+        let query_norms = queries
+            .rows()
+            .into_iter()
+            .map(|query| query.dot(&query).sqrt())
+            .collect::<Array1<f32>>();
+        let dots = self.word_matrix.matmul(&queries.t().to_owned());
+        let similarities = self.to_cos_similarities(dots, &query_norms);
+        similarities
+            .columns()
+            .into_iter()
+            .map(|column| top_k(column.iter().copied().enumerate(), top, exclude))
+            .collect()
+    }
+
+    // LSH で同じバケットに入った単語だけを候補として類似度を計算する
+    // 候補が top 個に満たない場合は、返す個数も top 個より少なくなる
+    pub fn search_approximate(
+        &self,
+        query: ArrayView1<f32>,
+        top: usize,
+        exclude: &[WordId],
+    ) -> Vec<(WordId, f32)> {
+        let lsh = self
+            .lsh
+            .as_ref()
+            .expect("SimilarityIndex must be built with with_lsh");
+
+        let query_norm = query.dot(&query).sqrt();
+        let candidates = lsh.candidates(query);
+        top_k(
+            candidates.into_iter().map(|word_id| {
+                let dot = self.word_matrix.row(word_id).dot(&query);
+                (
+                    word_id,
+                    dot / ((self.norms[word_id] + EPSILON) * (query_norm + EPSILON)),
+                )
+            }),
+            top,
+            exclude,
+        )
+    }
+
+    // 内積 (語彙数, 問い合わせ数) を各単語と各問い合わせのノルムで割る
+    fn to_cos_similarities(&self, dots: Array2<f32>, query_norms: &Array1<f32>) -> Array2<f32> {
+        let norms = (&self.norms + EPSILON).insert_axis(Axis(1));
+        dots / norms / (query_norms + EPSILON)
+    }
+}
+
+// スコアの大きい順に top 個を選ぶ
+// ヒープには（スコアの小さいものが先頭に来るように）Reverse で包んで入れ、
+// top 個を超えたら最小のものを捨てる
+pub(crate) fn top_k<I>(scores: I, top: usize, exclude: &[WordId]) -> Vec<(WordId, f32)>
+where
+    I: IntoIterator<Item = (WordId, f32)>,
+{
+    let mut heap = BinaryHeap::with_capacity(top + 1);
+    for (word_id, score) in scores {
+        if exclude.contains(&word_id) {
+            continue;
+        }
+        heap.push(Reverse(Scored { score, word_id }));
+        if heap.len() > top {
+            heap.pop();
+        }
+    }
+
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|Reverse(Scored { score, word_id })| (word_id, score))
+        .collect()
+}
+
+// スコアが同じ場合は単語 ID の小さい方を上位とする
+#[derive(PartialEq)]
+struct Scored {
+    score: f32,
+    word_id: WordId,
+}
+
+impl Eq for Scored {}
+
+impl PartialOrd for Scored {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Scored {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.score
+            .total_cmp(&other.score)
+            .then_with(|| other.word_id.cmp(&self.word_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use ndarray::{array, Axis};
+
+    use crate::{
+        corpus::Corpus,
+        util::cos_similarity::cos_similarity,
+        word_matrix::{co_matrix::CoMatrix, sparse_co_matrix::SparseCoMatrix},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_top_k() {
+        let scores = [(0, 0.5), (1, 0.9), (2, -0.3), (3, 0.9), (4, 0.7)];
+        assert_eq!(top_k(scores, 3, &[]), vec![(1, 0.9), (3, 0.9), (4, 0.7)]);
+        assert_eq!(top_k(scores, 2, &[1]), vec![(3, 0.9), (4, 0.7)]);
+        assert_eq!(top_k(scores, 10, &[]).len(), 5);
+    }
+
+    #[test]
+    fn test_search() {
+        let text = "You say goodbye and I say hello.";
+        let corpus = Corpus::new(text);
+        let co_matrix = CoMatrix::new(&corpus, 1);
+        let index = SimilarityIndex::new(&co_matrix);
+
+        // 総当たりで計算したコサイン類似度と一致する
+        let query_id = corpus.word_to_id("you").unwrap();
+        let query = co_matrix.row(query_id);
+        let result = index.search(query.view(), 3, &[query_id]);
+        assert_eq!(
+            result
+                .iter()
+                .map(|&(word_id, _)| word_id)
+                .collect::<Vec<_>>(),
+            vec![2, 4, 5] // goodbye, i, hello
+        );
+        for &(word_id, similarity) in &result {
+            assert_abs_diff_eq!(
+                similarity,
+                cos_similarity(query.view(), co_matrix.row(word_id).view(), None),
+                epsilon = 1e-6
+            );
+        }
+
+        // まとめて問い合わせても、１つずつ問い合わせた結果と同じ
+        let queries = co_matrix.matrix().select(Axis(0), &[0, 1, 6]);
+        let batch = index.search_batch(queries.view(), 3, &[]);
+        for (result, query) in batch.iter().zip(queries.rows()) {
+            assert_eq!(result, &index.search(query, 3, &[]));
+        }
+
+        // 単語 ID で問い合わせても同じ類似度になる
+        let similarities = index.cos_similarities(&[0, 1, 6]);
+        for (result, column) in batch.iter().zip(similarities.columns()) {
+            for &(word_id, similarity) in result {
+                assert_abs_diff_eq!(column[word_id], similarity, epsilon = 1e-6);
+            }
+        }
+        assert_eq!(index.norms(), co_matrix.matrix().row_norms());
+
+        // 疎行列のまま持っても同じ結果になる
+        let sparse = SparseCoMatrix::new(&corpus, 1);
+        let sparse_index = SimilarityIndex::new(&sparse);
+        assert_eq!(sparse_index.search(query.view(), 3, &[query_id]), result);
+        assert_eq!(sparse_index.cos_similarities(&[0, 1, 6]), similarities);
+    }
+
+    #[test]
+    fn test_search_approximate() {
+        let word_matrix = array![
+            [1., 0.1],
+            [1., 0.2],
+            [0.9, 0.],
+            [-1., 0.1],
+            [-1., -0.2],
+            [0.1, -1.]
+        ];
+
+        // ビット数が０なら、すべての単語が同じバケットに入るので厳密な探索と一致する
+        let index = SimilarityIndex::with_lsh(
+            &word_matrix,
+            InitParamsOfLsh {
+                number_of_tables: 1,
+                number_of_bits: 0,
+            },
+            &mut rand::thread_rng(),
+        );
+        let query = array![1., 0.];
+        assert_eq!(
+            index.search_approximate(query.view(), 3, &[]),
+            index.search(query.view(), 3, &[])
+        );
+
+        // 候補は厳密な探索の結果の部分集合で、類似度の大きい順に並ぶ
+        let index = SimilarityIndex::with_lsh(
+            &word_matrix,
+            InitParamsOfLsh {
+                number_of_tables: 2,
+                number_of_bits: 2,
+            },
+            &mut rand::thread_rng(),
+        );
+        let exact = index.search(query.view(), 6, &[]);
+        let approximate = index.search_approximate(query.view(), 6, &[]);
+        assert!(approximate.iter().all(|result| exact.contains(result)));
+        assert!(approximate.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    }
+}
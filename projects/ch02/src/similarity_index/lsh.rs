@@ -0,0 +1,127 @@
+/*
+    ランダム超平面による LSH (Locality Sensitive Hashing)
+
+    number_of_bits 枚のランダムな超平面のどちら側にあるかでベクトルを符号化し、
+    同じ符号（バケット）に入った単語を近傍の候補とする
+    コサイン類似度が大きいほど同じバケットに入りやすい
+    超平面のどちら側にあるかはベクトルの長さによらないので、単語ベクトルは正規化せずに符号化する
+
+    取りこぼしを減らすため、独立な超平面の組（テーブル）を number_of_tables 個用意し、
+    いずれかのテーブルで同じバケットに入った単語をすべて候補とする
+*/
+
+use std::collections::{BTreeSet, HashMap};
+
+use ndarray::{Array2, ArrayView1, ArrayView2};
+use ndarray_rand::{rand_distr::StandardNormal, RandomExt};
+use rand::Rng;
+
+use crate::{corpus::WordId, word_matrix::WordMatrix};
+
+pub struct InitParamsOfLsh {
+    pub number_of_tables: usize,
+    // バケットの符号のビット数（64 以下）
+    pub number_of_bits: usize,
+}
+
+pub(crate) struct Lsh {
+    tables: Vec<LshTable>,
+}
+
+struct LshTable {
+    // (次元, ビット数)
+    hyperplanes: Array2<f32>,
+    buckets: HashMap<u64, Vec<WordId>>,
+}
+
+impl Lsh {
+    pub(crate) fn new<T: WordMatrix, R: Rng>(
+        word_matrix: &T,
+        params: InitParamsOfLsh,
+        rng: &mut R,
+    ) -> Self {
+        let InitParamsOfLsh {
+            number_of_tables,
+            number_of_bits,
+        } = params;
+        assert!(number_of_bits <= 64);
+
+        let (_, dim) = word_matrix.shape();
+        let tables = (0..number_of_tables)
+            .map(|_| {
+                let hyperplanes = Array2::random_using((dim, number_of_bits), StandardNormal, rng);
+                let mut buckets = HashMap::<u64, Vec<WordId>>::new();
+                let projections = word_matrix.matmul(&hyperplanes);
+                for (word_id, code) in encode(projections.view()).into_iter().enumerate() {
+                    buckets.entry(code).or_default().push(word_id);
+                }
+                LshTable {
+                    hyperplanes,
+                    buckets,
+                }
+            })
+            .collect();
+
+        Self { tables }
+    }
+
+    // いずれかのテーブルで query と同じバケットに入っている単語（単語 ID の昇順）
+    pub(crate) fn candidates(&self, query: ArrayView1<f32>) -> BTreeSet<WordId> {
+        let query = query.insert_axis(ndarray::Axis(0));
+        self.tables
+            .iter()
+            .flat_map(|table| {
+                let code = encode(query.dot(&table.hyperplanes).view())[0];
+                table.buckets.get(&code).into_iter().flatten().copied()
+            })
+            .collect()
+    }
+}
+
+// 超平面との内積 (単語数, ビット数) の各行について、i 番目が正なら i ビット目を立てる
+fn encode(projections: ArrayView2<f32>) -> Vec<u64> {
+    projections
+        .rows()
+        .into_iter()
+        .map(|projections| {
+            projections
+                .iter()
+                .enumerate()
+                .filter(|(_, &projection)| projection > 0.)
+                .fold(0, |code, (i, _)| code | (1 << i))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        let vectors = array![[1., 1.], [-1., 2.], [-1., -1.]];
+        let hyperplanes = array![[1., 0.], [0., 1.]];
+        let projections = vectors.dot(&hyperplanes);
+        assert_eq!(encode(projections.view()), vec![0b11, 0b10, 0b00]);
+    }
+
+    #[test]
+    fn test_candidates() {
+        let word_vectors = array![[1., 0.], [0.9, 0.1], [-1., 0.], [-0.9, -0.1]];
+        let lsh = Lsh::new(
+            &word_vectors,
+            InitParamsOfLsh {
+                number_of_tables: 3,
+                number_of_bits: 4,
+            },
+            &mut rand::thread_rng(),
+        );
+
+        // 自分自身は必ず候補に入り、真逆を向いたベクトルは同じバケットに入らない
+        let candidates = lsh.candidates(word_vectors.row(0));
+        assert!(candidates.contains(&0));
+        assert!(!candidates.contains(&2));
+    }
+}
@@ -0,0 +1,120 @@
+/*
+    a : b = c : ? を解く
+    各単語ベクトルを正規化したうえで、次のスコアが大きい単語 d を答えとする
+
+    3CosAdd: cos(d, b) - cos(d, a) + cos(d, c)
+    3CosMul: cos'(d, b) cos'(d, c) / (cos'(d, a) + ε)
+      where cos'(x, y) = (cos(x, y) + 1) / 2  (負の値を取らないように [0, 1] に変換)
+
+    <https://aclanthology.org/W14-1618/> (Levy and Goldberg, 2014)
+*/
+
+use crate::{
+    corpus::{Corpus, Word, WordId},
+    word_matrix::WordMatrix,
+};
+
+use super::{cos_similarity::normalize, query_error::QueryError};
+
+const EPSILON: f32 = 1e-3;
+
+#[derive(Clone, Copy)]
+pub enum AnalogyMethod {
+    CosAdd,
+    CosMul,
+}
+
+pub fn analogy<T: WordMatrix>(
+    (a, b, c): (&str, &str, &str),
+    corpus: &Corpus,
+    word_matrix: &T,
+    top: usize,
+    method: AnalogyMethod,
+) -> Result<Vec<(Word, f32)>, QueryError> {
+    let word_id = |word: &str| {
+        corpus
+            .word_to_id(word)
+            .ok_or_else(|| QueryError::UnknownWord(word.to_string()))
+    };
+    let query_ids: [WordId; 3] = [word_id(a)?, word_id(b)?, word_id(c)?];
+
+    let [a_vec, b_vec, c_vec] =
+        query_ids.map(|word_id| normalize(word_matrix.row(word_id).view(), None));
+
+    let (vocab_size, _) = word_matrix.shape();
+    let mut scores = (0..vocab_size)
+        .filter(|word_id| !query_ids.contains(word_id))
+        .map(|word_id| {
+            let d_vec = normalize(word_matrix.row(word_id).view(), None);
+            let (cos_a, cos_b, cos_c) = (d_vec.dot(&a_vec), d_vec.dot(&b_vec), d_vec.dot(&c_vec));
+            let score = match method {
+                AnalogyMethod::CosAdd => cos_b - cos_a + cos_c,
+                AnalogyMethod::CosMul => {
+                    let [cos_a, cos_b, cos_c] = [cos_a, cos_b, cos_c].map(|cos| (cos + 1.) / 2.);
+                    cos_b * cos_c / (cos_a + EPSILON)
+                }
+            };
+            (word_id, score)
+        })
+        .collect::<Vec<_>>();
+    scores.sort_by(|(_, x), (_, y)| y.partial_cmp(x).unwrap());
+
+    Ok(scores
+        .into_iter()
+        .take(top)
+        .map(|(word_id, score)| (corpus.id_to_word[&word_id].clone(), score))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn test_analogy() {
+        let text = "man woman king queen apple";
+        let corpus = Corpus::new(text);
+        let word_matrix = array![
+            [1., 0., 0.],  // man
+            [1., 1., 0.],  // woman
+            [1., 0., 1.],  // king
+            [1., 1., 1.],  // queen
+            [0., 0., -1.], // apple
+        ];
+
+        for method in [AnalogyMethod::CosAdd, AnalogyMethod::CosMul] {
+            let answers =
+                analogy(("man", "king", "woman"), &corpus, &word_matrix, 2, method).unwrap();
+            assert_eq!(answers.len(), 2);
+            assert_eq!(answers[0].0, "queen");
+            assert_eq!(answers[1].0, "apple");
+            assert!(answers[0].1 > answers[1].1);
+        }
+
+        // 3CosAdd のスコア
+        let answers = analogy(
+            ("man", "king", "woman"),
+            &corpus,
+            &word_matrix,
+            1,
+            AnalogyMethod::CosAdd,
+        )
+        .unwrap();
+        let expected = 2. / 6_f32.sqrt() - 1. / 3_f32.sqrt() + 2. / 6_f32.sqrt();
+        assert!((answers[0].1 - expected).abs() < 1e-5);
+
+        // 語彙に含まれない単語
+        assert_eq!(
+            analogy(
+                ("man", "king", "princess"),
+                &corpus,
+                &word_matrix,
+                1,
+                AnalogyMethod::CosAdd
+            ),
+            Err(QueryError::UnknownWord("princess".to_string()))
+        );
+    }
+}
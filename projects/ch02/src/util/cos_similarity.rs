@@ -1,15 +1,19 @@
-use ndarray::ArrayView1;
+use ndarray::{Array1, ArrayView1};
 use ndarray_linalg::Norm;
 
 const EPSILON: f32 = 1e-8;
 
-pub(crate) fn cos_similarity(x: ArrayView1<f32>, y: ArrayView1<f32>, epsilon: Option<f32>) -> f32 {
-    let epsilon = epsilon.unwrap_or(EPSILON);
-    let nx = x.to_owned() / (x.norm_l2() + epsilon);
-    let ny = y.to_owned() / (y.norm_l2() + epsilon);
+pub fn cos_similarity(x: ArrayView1<f32>, y: ArrayView1<f32>, epsilon: Option<f32>) -> f32 {
+    let nx = normalize(x, epsilon);
+    let ny = normalize(y, epsilon);
     nx.dot(&ny)
 }
 
+pub(crate) fn normalize(x: ArrayView1<f32>, epsilon: Option<f32>) -> Array1<f32> {
+    let epsilon = epsilon.unwrap_or(EPSILON);
+    x.to_owned() / (x.norm_l2() + epsilon)
+}
+
 #[cfg(test)]
 mod tests {
     use approx::assert_abs_diff_eq;
@@ -0,0 +1,127 @@
+/*
+    学習済みの言語モデルで start_word から始まる文章を生成し、単語を空白で区切って並べる
+    語彙を刈り込んだコーパスでは、語彙にない start_word を UNKNOWN_WORD として扱う
+*/
+
+use neural_network::{
+    matrix::{
+        matrix_one_dim::MatrixOneDim, matrix_three_dim::MatrixThreeDim,
+        matrix_two_dim::MatrixTwoDim,
+    },
+    network::{
+        generation::{generate, InitParamsOfGeneration, Sampling},
+        language_model::LanguageModel,
+    },
+};
+use rand::Rng;
+
+use crate::corpus::{Corpus, Word};
+
+use super::query_error::QueryError;
+
+pub struct InitParamsOfTextGeneration {
+    // start_word を含めた単語数の上限（１以上）
+    pub max_len: usize,
+    // これらの単語が選ばれたら生成を終える（語彙にない単語は無視する）
+    pub stop_words: Vec<Word>,
+    pub sampling: Sampling,
+}
+
+pub fn generate_text<Net, M3, M2, M1, R>(
+    network: &mut Net,
+    corpus: &Corpus,
+    start_word: &str,
+    params: InitParamsOfTextGeneration,
+    rng: &mut R,
+) -> Result<String, QueryError>
+where
+    Net: LanguageModel<M3, M2, M1>,
+    M3: MatrixThreeDim<M2, M1>,
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+    R: Rng,
+{
+    let InitParamsOfTextGeneration {
+        max_len,
+        stop_words,
+        sampling,
+    } = params;
+
+    let start_id = corpus
+        .word_to_id_or_unknown(start_word)
+        .ok_or_else(|| QueryError::UnknownWord(start_word.to_string()))?;
+    let stop_ids = stop_words
+        .iter()
+        .filter_map(|word| corpus.word_to_id(word))
+        .collect();
+
+    let ids = generate(
+        network,
+        start_id,
+        InitParamsOfGeneration {
+            max_len,
+            stop_ids,
+            sampling,
+        },
+        rng,
+    );
+    let words = ids
+        .into_iter()
+        .map(|id| corpus.id_to_word(id).unwrap().as_str())
+        .collect::<Vec<_>>();
+    Ok(words.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use neural_network::network::test_support::CyclicLm;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::corpus::vocabulary::InitParamsOfVocabulary;
+
+    use super::*;
+
+    #[test]
+    fn test_generate_text() {
+        let corpus = Corpus::new("you say goodbye and i say hello .");
+        let mut network = CyclicLm {
+            vocab_size: corpus.vocab_size(),
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+        let params = |stop_words: &[&str]| InitParamsOfTextGeneration {
+            max_len: 10,
+            stop_words: stop_words.iter().map(|word| word.to_string()).collect(),
+            sampling: Sampling::TopK {
+                k: 1,
+                temperature: 1.,
+            },
+        };
+
+        let text = generate_text(&mut network, &corpus, "and", params(&["."]), &mut rng);
+        assert_eq!(text, Ok("and i hello".to_string()));
+
+        // 語彙にない停止語は無視して max_len まで生成する
+        let text = generate_text(&mut network, &corpus, "hello", params(&["<eos>"]), &mut rng);
+        assert_eq!(
+            text,
+            Ok("hello . you say goodbye and i hello . you".to_string())
+        );
+
+        let text = generate_text(&mut network, &corpus, "hi", params(&[]), &mut rng);
+        assert_eq!(text, Err(QueryError::UnknownWord("hi".to_string())));
+
+        // 刈り込んだ語彙では UNKNOWN_WORD から生成する
+        let pruned = corpus.prune_vocabulary(InitParamsOfVocabulary {
+            min_count: 2,
+            max_size: None,
+        });
+        let mut network = CyclicLm {
+            vocab_size: pruned.vocab_size(),
+        };
+        let text = generate_text(&mut network, &pruned, "hi", params(&[]), &mut rng);
+        assert_eq!(
+            text,
+            Ok("<unk> say <unk> say <unk> say <unk> say <unk> say".to_string())
+        );
+    }
+}
@@ -1,2 +1,5 @@
-pub(crate) mod cos_similarity;
+pub mod analogy;
+pub mod cos_similarity;
+pub mod generate_text;
 pub mod most_similar;
+pub mod query_error;
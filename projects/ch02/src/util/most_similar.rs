@@ -2,10 +2,21 @@ use std::io::{self, Write};
 
 use crate::{
     corpus::{Corpus, Word},
+    similarity_index::{top_k, SimilarityIndex},
     word_matrix::WordMatrix,
 };
 
-use super::cos_similarity::cos_similarity;
+use super::query_error::QueryError;
+
+const EPSILON: f32 = 1e-8;
+
+#[derive(Clone, Copy)]
+pub enum SimilarityMetric {
+    Cosine,
+    Dot,
+    // ユークリッド距離（小さいほど似ている）
+    Euclidean,
+}
 
 pub fn print_most_similar<T: WordMatrix>(
     query: Word,
@@ -15,67 +26,235 @@ pub fn print_most_similar<T: WordMatrix>(
 ) {
     let stdout = io::stdout();
     let mut stdout = stdout.lock();
-    most_similar(&mut stdout, query, corpus, word_matrix, top).unwrap();
+    write_most_similar(&mut stdout, query, corpus, word_matrix, top).unwrap();
 }
 
-fn most_similar<W: Write, T: WordMatrix>(
+fn write_most_similar<W: Write, T: WordMatrix>(
     w: &mut W,
     query: Word,
     corpus: &Corpus,
     word_matrix: &T,
     top: usize,
 ) -> io::Result<()> {
-    if let None = corpus.word_to_id.get(&query) {
-        writeln!(w, "{} is not found", query)?;
-        return Ok(());
-    }
+    let similar_words =
+        match most_similar(&query, corpus, word_matrix, top, SimilarityMetric::Cosine) {
+            Ok(similar_words) => similar_words,
+            Err(e) => {
+                writeln!(w, "{}", e)?;
+                return Ok(());
+            }
+        };
 
     writeln!(w, "\n[query] {}", query)?;
-
-    let query_id = corpus.word_to_id[&query];
-    let matrix = word_matrix.view();
-    let query_vec = matrix.row(query_id);
-    let id_to_word = &corpus.id_to_word;
-
-    let mut cos_similarities = word_matrix
-        .view()
-        .rows()
-        .into_iter()
-        .enumerate()
-        .filter(|&(word_id, _)| word_id != query_id)
-        .map(|(word_id, word_vec)| {
-            (
-                id_to_word.get(&word_id).unwrap(),
-                cos_similarity(query_vec, word_vec, None),
-            )
-        })
-        .collect::<Vec<_>>();
-    cos_similarities.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap());
-
-    for (word, cos_similarity) in cos_similarities.into_iter().take(top) {
+    for (word, cos_similarity) in similar_words {
         writeln!(w, "{}: {}", word, cos_similarity)?;
     }
 
     Ok(())
 }
 
+// query に似ている順に top 個の単語とその類似度（Euclidean の場合は距離）を返す
+pub fn most_similar<T: WordMatrix>(
+    query: &str,
+    corpus: &Corpus,
+    word_matrix: &T,
+    top: usize,
+    metric: SimilarityMetric,
+) -> Result<Vec<(Word, f32)>, QueryError> {
+    most_similar_in(
+        query,
+        corpus,
+        &SimilarityIndex::new(word_matrix),
+        top,
+        metric,
+    )
+}
+
+// 同じ単語行列に何度も問い合わせる場合は、SimilarityIndex を一度だけ作ってこちらを使う
+pub fn most_similar_in<T: WordMatrix>(
+    query: &str,
+    corpus: &Corpus,
+    index: &SimilarityIndex<T>,
+    top: usize,
+    metric: SimilarityMetric,
+) -> Result<Vec<(Word, f32)>, QueryError> {
+    let mut results = most_similar_batch_in(&[query], corpus, index, top, metric)?;
+    Ok(results.pop().unwrap())
+}
+
+// 問い合わせに語彙にない単語が含まれる場合はエラーを返す
+pub fn most_similar_batch<T: WordMatrix>(
+    queries: &[&str],
+    corpus: &Corpus,
+    word_matrix: &T,
+    top: usize,
+    metric: SimilarityMetric,
+) -> Result<Vec<Vec<(Word, f32)>>, QueryError> {
+    most_similar_batch_in(
+        queries,
+        corpus,
+        &SimilarityIndex::new(word_matrix),
+        top,
+        metric,
+    )
+}
+
+// 全単語との内積を、すべての問い合わせについて１回の行列積で計算する
+// コサイン類似度とユークリッド距離は、内積とインデックスが持つノルムから求める
+//   cos(x, y) = x・y / |x| |y|
+//   |x - y| = √(|x|^2 + |y|^2 - 2 x・y)
+pub fn most_similar_batch_in<T: WordMatrix>(
+    queries: &[&str],
+    corpus: &Corpus,
+    index: &SimilarityIndex<T>,
+    top: usize,
+    metric: SimilarityMetric,
+) -> Result<Vec<Vec<(Word, f32)>>, QueryError> {
+    let query_ids = queries
+        .iter()
+        .map(|&query| {
+            corpus
+                .word_to_id(query)
+                .ok_or_else(|| QueryError::UnknownWord(query.to_string()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // (語彙数, 問い合わせ数)
+    let dots = index.dots(&query_ids);
+    let norms = index.norms();
+
+    let results = query_ids
+        .iter()
+        .zip(dots.columns())
+        .map(|(&query_id, dots)| {
+            let query_norm = norms[query_id];
+            // top_k はスコアの大きい順に選ぶので、距離は符号を反転させておく
+            let scores = dots.iter().zip(norms).map(|(&dot, &norm)| match metric {
+                SimilarityMetric::Cosine => dot / ((norm + EPSILON) * (query_norm + EPSILON)),
+                SimilarityMetric::Dot => dot,
+                SimilarityMetric::Euclidean => -(norm * norm + query_norm * query_norm - 2. * dot)
+                    .max(0.)
+                    .sqrt(),
+            });
+
+            top_k(scores.enumerate(), top, &[query_id])
+                .into_iter()
+                .map(|(word_id, score)| {
+                    let score = match metric {
+                        SimilarityMetric::Euclidean => -score,
+                        _ => score,
+                    };
+                    (corpus.id_to_word[&word_id].clone(), score)
+                })
+                .collect()
+        })
+        .collect();
+
+    Ok(results)
+}
+
 // <https://teratail.com/questions/126598> を参考に実装
 #[cfg(test)]
 mod tests {
-    use crate::word_matrix::co_matrix::CoMatrix;
+    use approx::assert_abs_diff_eq;
+    use ndarray::array;
+
+    use crate::word_matrix::{co_matrix::CoMatrix, sparse_co_matrix::SparseCoMatrix};
 
     use super::*;
 
     #[test]
-    fn test_most_similar() {
+    fn test_write_most_similar() {
         let text = "You say goodbye and I say hello.";
         let corpus = Corpus::new(text);
         let co_matrix = CoMatrix::new(&corpus, 1);
 
         let mut w = Vec::<u8>::new();
-        most_similar(&mut w, "you".to_string(), &corpus, &co_matrix, 5).unwrap();
+        write_most_similar(&mut w, "you".to_string(), &corpus, &co_matrix, 5).unwrap();
 
         let expected = "\n[query] you\ngoodbye: 0.70710677\ni: 0.70710677\nhello: 0.70710677\nsay: 0\nand: 0\n";
         assert_eq!(String::from_utf8(w).unwrap(), expected);
+
+        let mut w = Vec::<u8>::new();
+        write_most_similar(&mut w, "me".to_string(), &corpus, &co_matrix, 5).unwrap();
+        assert_eq!(String::from_utf8(w).unwrap(), "me is not found\n");
+    }
+
+    #[test]
+    fn test_most_similar() {
+        let corpus = Corpus::new("a b c d");
+        let word_matrix = array![[1., 0.], [3., 0.], [0.5, 0.5], [-1., 0.]];
+
+        let words = |results: &[(Word, f32)]| {
+            results
+                .iter()
+                .map(|(word, _)| word.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let cosine = most_similar("a", &corpus, &word_matrix, 3, SimilarityMetric::Cosine).unwrap();
+        assert_eq!(words(&cosine), vec!["b", "c", "d"]);
+        assert_abs_diff_eq!(cosine[1].1, 1. / 2_f32.sqrt(), epsilon = 1e-6);
+
+        let dot = most_similar("a", &corpus, &word_matrix, 3, SimilarityMetric::Dot).unwrap();
+        assert_eq!(words(&dot), vec!["b", "c", "d"]);
+        assert_eq!(
+            dot,
+            vec![
+                ("b".to_string(), 3.),
+                ("c".to_string(), 0.5),
+                ("d".to_string(), -1.)
+            ]
+        );
+
+        let euclidean =
+            most_similar("a", &corpus, &word_matrix, 3, SimilarityMetric::Euclidean).unwrap();
+        assert_eq!(words(&euclidean), vec!["c", "b", "d"]);
+        assert_abs_diff_eq!(euclidean[0].1, 0.5_f32.sqrt(), epsilon = 1e-6);
+        assert_abs_diff_eq!(euclidean[1].1, 2., epsilon = 1e-6);
+
+        assert_eq!(
+            most_similar("e", &corpus, &word_matrix, 3, SimilarityMetric::Cosine),
+            Err(QueryError::UnknownWord("e".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_most_similar_batch() {
+        let text = "You say goodbye and I say hello.";
+        let corpus = Corpus::new(text);
+        let co_matrix = CoMatrix::new(&corpus, 1);
+
+        let queries = ["you", "say", "hello"];
+        let batch =
+            most_similar_batch(&queries, &corpus, &co_matrix, 3, SimilarityMetric::Cosine).unwrap();
+        for (query, result) in queries.iter().zip(batch) {
+            assert_eq!(
+                result,
+                most_similar(query, &corpus, &co_matrix, 3, SimilarityMetric::Cosine).unwrap()
+            );
+        }
+
+        // 疎行列のまま問い合わせても同じ結果になる
+        let sparse_co_matrix = SparseCoMatrix::new(&corpus, 1);
+        for metric in [
+            SimilarityMetric::Cosine,
+            SimilarityMetric::Dot,
+            SimilarityMetric::Euclidean,
+        ] {
+            assert_eq!(
+                most_similar_batch(&queries, &corpus, &sparse_co_matrix, 3, metric),
+                most_similar_batch(&queries, &corpus, &co_matrix, 3, metric)
+            );
+        }
+
+        assert!(most_similar_batch(
+            &["you", "me"],
+            &corpus,
+            &co_matrix,
+            3,
+            SimilarityMetric::Dot
+        )
+        .is_err());
     }
 }
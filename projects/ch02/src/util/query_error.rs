@@ -0,0 +1,19 @@
+use std::{error::Error, fmt};
+
+use crate::corpus::Word;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryError {
+    // 語彙に含まれない単語が問い合わせられた
+    UnknownWord(Word),
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryError::UnknownWord(word) => write!(f, "{} is not found", word),
+        }
+    }
+}
+
+impl Error for QueryError {}
@@ -0,0 +1,176 @@
+/*
+    単語ベクトルを２次元に射影して散布図として描画する
+
+    - pca: 主成分分析（分散の大きい２方向への射影）
+    - tsne: t-SNE（近傍の関係を保つような非線形の射影）
+*/
+
+use std::ops::Range;
+
+use anyhow::Result;
+use ndarray::{s, Array2, Axis};
+use ndarray_linalg::SVD;
+use plotters::prelude::*;
+
+use crate::{corpus::Corpus, util::query_error::QueryError, word_matrix::WordMatrix};
+
+pub mod tsne;
+
+// words の単語ベクトルを並べた行列 (単語数, 次元)
+pub fn select_word_vectors<T: WordMatrix>(
+    words: &[&str],
+    corpus: &Corpus,
+    word_matrix: &T,
+) -> Result<Array2<f32>, QueryError> {
+    let (_, dim) = word_matrix.shape();
+    let mut vectors = Array2::zeros((words.len(), dim));
+    for (mut row, &word) in vectors.rows_mut().into_iter().zip(words) {
+        let word_id = corpus
+            .word_to_id(word)
+            .ok_or_else(|| QueryError::UnknownWord(word.to_string()))?;
+        row.assign(&word_matrix.row(word_id));
+    }
+    Ok(vectors)
+}
+
+// 中心化した X = U S V^T に対して、第１・第２主成分の座標 U S の先頭２列を返す
+// （符号は一意に定まらない）
+pub fn pca(vectors: &Array2<f32>) -> Array2<f32> {
+    let (number_of_words, _) = vectors.dim();
+    let mean = vectors.mean_axis(Axis(0)).unwrap();
+    let centered = vectors - &mean;
+
+    let (u, singular_values, _) = centered.svd(true, false).unwrap();
+    let u = u.unwrap();
+
+    // 次元数が２未満の場合は残りを０で埋める
+    let k = singular_values.len().min(2);
+    let mut points = Array2::zeros((number_of_words, 2));
+    points
+        .slice_mut(s![.., ..k])
+        .assign(&(&u.slice(s![.., ..k]) * &singular_values.slice(s![..k])));
+    points
+}
+
+pub fn plot_words(
+    words: &[&str],
+    points: &Array2<f32>,
+    out_path: &str,
+    caption: &str,
+) -> Result<()> {
+    assert_eq!(words.len(), points.dim().0);
+    assert_eq!(points.dim().1, 2);
+
+    // 背景の作成
+    let root = BitMapBackend::new(out_path, (800, 800)).into_drawing_area();
+    root.fill(&WHITE)?;
+
+    // グラフの描画範囲の設定
+    let mut chart = ChartBuilder::on(&root)
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .margin(20)
+        .caption(caption, ("sans-serif", 32.0).into_font())
+        .build_cartesian_2d(axis_range(points, 0), axis_range(points, 1))?;
+
+    chart
+        .configure_mesh()
+        .disable_mesh()
+        .x_label_formatter(&|x| format!("{:.1}", x))
+        .y_label_formatter(&|y| format!("{:.1}", y))
+        .label_style(FontDesc::new(FontFamily::SansSerif, 16., FontStyle::Normal))
+        .draw()?;
+
+    // 各単語を点とラベルで描画
+    chart.draw_series(words.iter().zip(points.rows()).map(|(word, point)| {
+        EmptyElement::at((point[0], point[1]))
+            + Circle::new((0, 0), 4, ShapeStyle::from(&BLUE).filled())
+            + Text::new(
+                word.to_string(),
+                (6, -8),
+                FontDesc::new(FontFamily::SansSerif, 16., FontStyle::Normal),
+            )
+    }))?;
+
+    // グラフの描画
+    root.present()?;
+    println!("Result has been saved to {}", out_path);
+    Ok(())
+}
+
+// ラベルがはみ出さないよう、column 列目の値の範囲に余白を取る
+fn axis_range(points: &Array2<f32>, column: usize) -> Range<f32> {
+    let values = points.column(column);
+    let min = values.iter().copied().fold(f32::INFINITY, f32::min);
+    let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let margin = ((max - min) * 0.1).max(1e-3);
+    (min - margin)..(max + margin)
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn test_select_word_vectors() {
+        let corpus = Corpus::new("a b c");
+        let word_matrix = array![[1., 2.], [3., 4.], [5., 6.]];
+
+        assert_eq!(
+            select_word_vectors(&["c", "a"], &corpus, &word_matrix),
+            Ok(array![[5., 6.], [1., 2.]])
+        );
+        assert_eq!(
+            select_word_vectors(&["d"], &corpus, &word_matrix),
+            Err(QueryError::UnknownWord("d".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_pca() {
+        // (1, 1, 0) 方向の直線上の点と、それに直交する (0, 0, 1) 方向のずれ
+        let vectors = array![[0., 0., 0.1], [1., 1., -0.1], [2., 2., -0.1], [3., 3., 0.1]];
+        let points = pca(&vectors);
+        assert_eq!(points.dim(), (4, 2));
+
+        // 第１主成分は直線に沿った座標（符号を除く）、第２主成分はずれ
+        let expected = [-1.5, -0.5, 0.5, 1.5].map(|x: f32| x * 2_f32.sqrt());
+        for (actual, expected) in points.column(0).iter().zip(expected) {
+            assert_abs_diff_eq!(actual.abs(), expected.abs(), epsilon = 1e-4);
+        }
+        for (actual, expected) in points.column(1).iter().zip([0.1_f32, -0.1, -0.1, 0.1]) {
+            assert_abs_diff_eq!(actual.abs(), expected.abs(), epsilon = 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_axis_range() {
+        let points = array![[0., 0.], [1., 0.5], [-1., 2.]];
+
+        let range = axis_range(&points, 0);
+        assert_abs_diff_eq!(range.start, -1.2, epsilon = 1e-6);
+        assert_abs_diff_eq!(range.end, 1.2, epsilon = 1e-6);
+        let range = axis_range(&points, 1);
+        assert_abs_diff_eq!(range.start, -0.2, epsilon = 1e-6);
+        assert_abs_diff_eq!(range.end, 2.2, epsilon = 1e-6);
+
+        // すべての点が同じ座標でも幅のある範囲になる
+        let range = axis_range(&array![[1., 1.], [1., 1.]], 0);
+        assert!(range.start < 1. && 1. < range.end);
+    }
+
+    // フォントが必要で PNG ファイルを書き出すので、cargo test -- --ignored で実行する
+    #[test]
+    #[ignore]
+    fn test_plot_words() {
+        let points = array![[0., 0.], [1., 0.5], [-1., 2.]];
+        let out_path = std::env::temp_dir().join("ch02_test_plot_words.png");
+        let out_path = out_path.to_str().unwrap();
+
+        plot_words(&["you", "say", "hello"], &points, out_path, "words").unwrap();
+        assert!(std::fs::metadata(out_path).unwrap().len() > 0);
+    }
+}
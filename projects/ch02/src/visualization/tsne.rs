@@ -0,0 +1,215 @@
+/*
+    t-SNE (van der Maaten and Hinton, 2008)
+
+    高次元での近さ P_ij（ガウス分布、各点の分散は perplexity から決める）と
+    ２次元での近さ Q_ij（自由度１の t 分布）の KL ダイバージェンスを勾配法で最小化する
+
+    dKL/dy_i = 4 Σ_j (P_ij - Q_ij) (y_i - y_j) / (1 + |y_i - y_j|^2)
+
+    点の数の２乗に比例する計算量の厳密な実装なので、数百語程度までを想定
+*/
+
+use ndarray::{Array1, Array2, Axis};
+use ndarray_rand::{rand_distr::Normal, RandomExt};
+use rand::Rng;
+
+pub struct InitParamsOfTsne {
+    // 各点が実質的に近傍とみなす点の数（点の数より小さくする）
+    pub perplexity: f32,
+    pub learning_rate: f32,
+    pub n_iter: usize,
+}
+
+// 最初の EXAGGERATION_ITER 回は P を EXAGGERATION 倍して、まとまりを作りやすくする
+const EXAGGERATION: f32 = 4.;
+const EXAGGERATION_ITER: usize = 100;
+const INITIAL_MOMENTUM: f32 = 0.5;
+const FINAL_MOMENTUM: f32 = 0.8;
+const MOMENTUM_SWITCH_ITER: usize = 20;
+const MIN_GAIN: f32 = 0.01;
+
+pub fn tsne<R: Rng>(vectors: &Array2<f32>, params: InitParamsOfTsne, rng: &mut R) -> Array2<f32> {
+    let InitParamsOfTsne {
+        perplexity,
+        learning_rate,
+        n_iter,
+    } = params;
+
+    let n = vectors.dim().0;
+    let p = joint_probabilities(&squared_distances(vectors), perplexity);
+
+    let mut y = Array2::random_using((n, 2), Normal::new(0., 1e-4).unwrap(), rng);
+    let mut update = Array2::<f32>::zeros((n, 2));
+    let mut gains = Array2::<f32>::ones((n, 2));
+
+    for iter in 0..n_iter {
+        let exaggeration = if iter < EXAGGERATION_ITER {
+            EXAGGERATION
+        } else {
+            1.
+        };
+        let momentum = if iter < MOMENTUM_SWITCH_ITER {
+            INITIAL_MOMENTUM
+        } else {
+            FINAL_MOMENTUM
+        };
+
+        // num_ij = 1 / (1 + |y_i - y_j|^2), Q_ij = num_ij / Σ num
+        let mut num = squared_distances(&y).mapv(|d| 1. / (1. + d));
+        num.diag_mut().fill(0.);
+        let q = (&num / num.sum()).mapv(|q| q.max(1e-12));
+
+        let mut grad = Array2::<f32>::zeros((n, 2));
+        for i in 0..n {
+            for j in 0..n {
+                let coefficient = 4. * (exaggeration * p[[i, j]] - q[[i, j]]) * num[[i, j]];
+                let diff = &y.row(i) - &y.row(j);
+                grad.row_mut(i).scaled_add(coefficient, &diff);
+            }
+        }
+
+        // 勾配の符号が前回の更新方向と逆向き（＝同じ方向に進み続けている）なら gain を増やす
+        for ((gain, &g), &u) in gains.iter_mut().zip(&grad).zip(&update) {
+            *gain = if (g > 0.) != (u > 0.) {
+                *gain + 0.2
+            } else {
+                (*gain * 0.8).max(MIN_GAIN)
+            };
+        }
+
+        update = &update * momentum - &(&gains * &grad) * learning_rate;
+        y += &update;
+        let mean = y.mean_axis(Axis(0)).unwrap();
+        y -= &mean;
+    }
+
+    y
+}
+
+// D_ij = |x_i - x_j|^2
+fn squared_distances(x: &Array2<f32>) -> Array2<f32> {
+    let squared_norms = x.map_axis(Axis(1), |row| row.dot(&row));
+    let gram = x.dot(&x.t());
+    let n = x.dim().0;
+    Array2::from_shape_fn((n, n), |(i, j)| {
+        (squared_norms[i] + squared_norms[j] - 2. * gram[[i, j]]).max(0.)
+    })
+}
+
+// 各点 i について条件付き確率 P_{j|i} ∝ exp(-β_i D_ij) のエントロピーが log(perplexity) に
+// なるよう β_i を二分探索し、P_ij = (P_{j|i} + P_{i|j}) / 2n とする
+fn joint_probabilities(distances: &Array2<f32>, perplexity: f32) -> Array2<f32> {
+    let n = distances.dim().0;
+    let target_entropy = perplexity.ln();
+    let mut conditional = Array2::<f32>::zeros((n, n));
+
+    for i in 0..n {
+        let (mut beta, mut beta_min, mut beta_max) = (1., 0., f32::INFINITY);
+        let mut p_i = Array1::zeros(n);
+
+        for _ in 0..100 {
+            // 数値的に安定させるため、最小の距離を引いてから exp を取る
+            let min_distance = (0..n)
+                .filter(|&j| j != i)
+                .map(|j| distances[[i, j]])
+                .fold(f32::INFINITY, f32::min);
+            p_i = Array1::from_shape_fn(n, |j| {
+                if j == i {
+                    0.
+                } else {
+                    (-beta * (distances[[i, j]] - min_distance)).exp()
+                }
+            });
+            let sum = p_i.sum();
+            p_i /= sum;
+
+            // H = -Σ p log p
+            let entropy = -p_i
+                .iter()
+                .filter(|&&p| p > 0.)
+                .map(|&p| p * p.ln())
+                .sum::<f32>();
+            if (entropy - target_entropy).abs() < 1e-5 {
+                break;
+            }
+
+            // エントロピーが大きすぎる（分布が広すぎる）なら β を大きくする
+            if entropy > target_entropy {
+                beta_min = beta;
+                beta = if beta_max.is_infinite() {
+                    beta * 2.
+                } else {
+                    (beta + beta_max) / 2.
+                };
+            } else {
+                beta_max = beta;
+                beta = (beta + beta_min) / 2.;
+            }
+        }
+
+        conditional.row_mut(i).assign(&p_i);
+    }
+
+    let p = (&conditional + &conditional.t()) / (2. * n as f32);
+    p.mapv(|p| p.max(1e-12))
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use ndarray::{array, Array1};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn test_joint_probabilities() {
+        let x = array![[0., 0.], [1., 0.], [0., 2.], [3., 3.]];
+        let distances = squared_distances(&x);
+        assert_eq!(distances[[1, 2]], 5.);
+
+        let p = joint_probabilities(&distances, 2.);
+        assert_abs_diff_eq!(p.sum(), 1., epsilon = 1e-5);
+        assert_eq!(p, p.t());
+    }
+
+    #[test]
+    fn test_tsne() {
+        // 10 次元空間で離れた２つのまとまり
+        let mut rng = StdRng::seed_from_u64(0);
+        let noise = Array2::random_using((10, 10), Normal::new(0., 0.1).unwrap(), &mut rng);
+        let mut vectors = noise;
+        for i in 0..5 {
+            vectors.row_mut(i)[0] += 5.;
+        }
+
+        let y = tsne(
+            &vectors,
+            InitParamsOfTsne {
+                perplexity: 3.,
+                learning_rate: 100.,
+                n_iter: 300,
+            },
+            &mut rng,
+        );
+        assert_eq!(y.dim(), (10, 2));
+
+        // 射影後も、同じまとまりの点どうしの方が、異なるまとまりの点より近い
+        let distance = |i: usize, j: usize| {
+            let diff: Array1<f32> = &y.row(i) - &y.row(j);
+            diff.dot(&diff).sqrt()
+        };
+        let cluster = |i: usize| i < 5;
+        let (mut max_within, mut min_between) = (0_f32, f32::INFINITY);
+        for i in 0..10 {
+            for j in (i + 1)..10 {
+                if cluster(i) == cluster(j) {
+                    max_within = max_within.max(distance(i, j));
+                } else {
+                    min_between = min_between.min(distance(i, j));
+                }
+            }
+        }
+        assert!(max_within < min_between);
+    }
+}
@@ -0,0 +1,203 @@
+/*
+    word2vec 形式での単語ベクトルの保存・読み込み
+
+    テキスト形式:
+    <語彙数> <次元数>
+    <単語> <v1> <v2> ...
+    ...
+
+    バイナリ形式:
+    <語彙数> <次元数>
+    <単語> <v1 ... vn を f32 (リトルエンディアン) で並べたもの>\n
+    ...
+
+    単語は単語 ID の順に書き出し、読み込むときは書かれている順に単語 ID を割り振る
+*/
+
+use std::io::{self, BufRead, Write};
+
+use ndarray::Array2;
+
+use crate::{
+    corpus::{Corpus, Word},
+    word_matrix::WordMatrix,
+};
+
+#[derive(Clone, Copy)]
+pub enum Word2VecFormat {
+    Text,
+    Binary,
+}
+
+pub fn save_word2vec<W: Write, T: WordMatrix>(
+    w: &mut W,
+    corpus: &Corpus,
+    word_matrix: &T,
+    format: Word2VecFormat,
+) -> io::Result<()> {
+    let (vocab_size, dim) = word_matrix.shape();
+    assert_eq!(vocab_size, corpus.vocab_size());
+
+    writeln!(w, "{} {}", vocab_size, dim)?;
+    for word_id in 0..vocab_size {
+        let word = corpus.id_to_word(word_id).unwrap();
+        let vector = word_matrix.row(word_id);
+        match format {
+            Word2VecFormat::Text => {
+                write!(w, "{}", word)?;
+                for value in vector.iter() {
+                    write!(w, " {}", value)?;
+                }
+                writeln!(w)?;
+            }
+            Word2VecFormat::Binary => {
+                write!(w, "{} ", word)?;
+                for value in vector.iter() {
+                    w.write_all(&value.to_le_bytes())?;
+                }
+                writeln!(w)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// 語彙だけを持つ（テキストが空の）Corpus と、単語ベクトルを並べた行列を返す
+pub fn load_word2vec<R: BufRead>(
+    r: &mut R,
+    format: Word2VecFormat,
+) -> io::Result<(Corpus, Array2<f32>)> {
+    let mut header = String::new();
+    r.read_line(&mut header)?;
+    let (vocab_size, dim) = match header
+        .split_whitespace()
+        .map(|value| value.parse::<usize>())
+        .collect::<Result<Vec<_>, _>>()
+        .as_deref()
+    {
+        Ok(&[vocab_size, dim]) => (vocab_size, dim),
+        _ => return Err(invalid_data(format!("invalid header: {}", header.trim()))),
+    };
+
+    let mut words = Vec::with_capacity(vocab_size);
+    let mut vectors = Vec::with_capacity(vocab_size * dim);
+    for _ in 0..vocab_size {
+        let (word, vector) = match format {
+            Word2VecFormat::Text => read_text_line(r, dim)?,
+            Word2VecFormat::Binary => read_binary_entry(r, dim)?,
+        };
+        words.push(word);
+        vectors.extend(vector);
+    }
+
+    let corpus = Corpus::from_vocabulary(words);
+    if corpus.vocab_size() != vocab_size {
+        return Err(invalid_data("duplicate words".to_string()));
+    }
+    let word_vectors = Array2::from_shape_vec((vocab_size, dim), vectors).unwrap();
+
+    Ok((corpus, word_vectors))
+}
+
+fn read_text_line<R: BufRead>(r: &mut R, dim: usize) -> io::Result<(Word, Vec<f32>)> {
+    let mut line = String::new();
+    if r.read_line(&mut line)? == 0 {
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+    }
+
+    let mut columns = line.split_whitespace();
+    let word = columns
+        .next()
+        .ok_or_else(|| invalid_data("empty line".to_string()))?
+        .to_string();
+    let vector = columns
+        .map(|value| value.parse::<f32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| invalid_data(format!("{}: {}", word, e)))?;
+    if vector.len() != dim {
+        return Err(invalid_data(format!(
+            "{}: expected {} values, found {}",
+            word,
+            dim,
+            vector.len()
+        )));
+    }
+
+    Ok((word, vector))
+}
+
+fn read_binary_entry<R: BufRead>(r: &mut R, dim: usize) -> io::Result<(Word, Vec<f32>)> {
+    // 前のベクトルの後ろの改行は読み飛ばす
+    let mut word = vec![];
+    r.read_until(b' ', &mut word)?;
+    if word.last() != Some(&b' ') {
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+    }
+    word.pop();
+    let word = String::from_utf8(word)
+        .map_err(|e| invalid_data(e.to_string()))?
+        .trim_start_matches('\n')
+        .to_string();
+
+    let mut bytes = vec![0_u8; 4 * dim];
+    r.read_exact(&mut bytes)?;
+    let vector = bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes(chunk.try_into().unwrap()))
+        .collect();
+
+    Ok((word, vector))
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn test_word2vec_format() {
+        let text = "You say goodbye.";
+        let corpus = Corpus::new(text);
+        let word_matrix = array![
+            [0.5, -1.25, 3.],
+            [1e-8, 0., -0.1],
+            [2., 4., 8.],
+            [-1., 1., 0.3],
+        ];
+
+        for format in [Word2VecFormat::Text, Word2VecFormat::Binary] {
+            let mut buffer = Vec::<u8>::new();
+            save_word2vec(&mut buffer, &corpus, &word_matrix, format).unwrap();
+            let (loaded_corpus, loaded_matrix) =
+                load_word2vec(&mut buffer.as_slice(), format).unwrap();
+
+            assert_eq!(loaded_corpus.word_to_id, corpus.word_to_id);
+            assert_eq!(loaded_corpus.id_to_word, corpus.id_to_word);
+            assert_eq!(loaded_matrix, word_matrix);
+        }
+    }
+
+    #[test]
+    fn test_load_word2vec_text() {
+        let file = "2 3\nyou 1 2 3\nsay 4 5 6\n";
+        let (corpus, word_matrix) =
+            load_word2vec(&mut file.as_bytes(), Word2VecFormat::Text).unwrap();
+        assert_eq!(corpus.word_to_id("say"), Some(1));
+        assert_eq!(word_matrix, array![[1., 2., 3.], [4., 5., 6.]]);
+
+        let file = "2 3\nyou 1 2 3\nsay 4 5\n";
+        assert!(load_word2vec(&mut file.as_bytes(), Word2VecFormat::Text).is_err());
+
+        let file = "2 3\nyou 1 2 3\nyou 4 5 6\n";
+        assert!(load_word2vec(&mut file.as_bytes(), Word2VecFormat::Text).is_err());
+
+        let file = "3 3\nyou 1 2 3\nsay 4 5 6\n";
+        assert!(load_word2vec(&mut file.as_bytes(), Word2VecFormat::Text).is_err());
+    }
+}
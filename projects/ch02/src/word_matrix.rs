@@ -1,7 +1,95 @@
-use ndarray::ArrayView2;
+use ndarray::{Array1, Array2, Axis, CowArray, Ix1};
+
+use crate::corpus::WordId;
 
 pub mod co_matrix;
+pub mod csr_matrix;
+pub mod sparse_co_matrix;
+pub mod svd;
 
 pub trait WordMatrix {
-    fn view(&self) -> ArrayView2<f32>;
+    // (語彙数, 単語ベクトルの次元)
+    fn shape(&self) -> (usize, usize);
+    // 密な行ベクトル（疎行列では行ごとに語彙数の長さの配列を確保するので、
+    // 全行をなめる処理では matmul などを使う）
+    fn row(&self, word_id: WordId) -> CowArray<'_, f32, Ix1>;
+
+    // 各行（単語ベクトル）の L2 ノルム
+    fn row_norms(&self) -> Array1<f32> {
+        let (vocab_size, _) = self.shape();
+        (0..vocab_size)
+            .map(|word_id| {
+                let row = self.row(word_id);
+                row.dot(&row).sqrt()
+            })
+            .collect()
+    }
+
+    // 行列積 self.dot(rhs) を計算する
+    fn matmul(&self, rhs: &Array2<f32>) -> Array2<f32> {
+        let (vocab_size, dim) = self.shape();
+        assert_eq!(dim, rhs.dim().0);
+        let mut out = Array2::zeros((vocab_size, rhs.dim().1));
+        for (word_id, mut out_row) in out.rows_mut().into_iter().enumerate() {
+            out_row.assign(&self.row(word_id).dot(rhs));
+        }
+        out
+    }
+
+    // 行列積 self^T.dot(rhs) を計算する
+    fn t_matmul(&self, rhs: &Array2<f32>) -> Array2<f32> {
+        let (vocab_size, dim) = self.shape();
+        assert_eq!(vocab_size, rhs.dim().0);
+        let mut out = Array2::zeros((dim, rhs.dim().1));
+        for (word_id, rhs_row) in rhs.rows().into_iter().enumerate() {
+            let row = self.row(word_id);
+            out += &row
+                .view()
+                .insert_axis(Axis(1))
+                .dot(&rhs_row.insert_axis(Axis(0)));
+        }
+        out
+    }
+}
+
+// 借用した単語行列も、持ち主の実装のまま使えるようにする
+impl<T: WordMatrix + ?Sized> WordMatrix for &T {
+    fn shape(&self) -> (usize, usize) {
+        (**self).shape()
+    }
+
+    fn row(&self, word_id: WordId) -> CowArray<'_, f32, Ix1> {
+        (**self).row(word_id)
+    }
+
+    fn row_norms(&self) -> Array1<f32> {
+        (**self).row_norms()
+    }
+
+    fn matmul(&self, rhs: &Array2<f32>) -> Array2<f32> {
+        (**self).matmul(rhs)
+    }
+
+    fn t_matmul(&self, rhs: &Array2<f32>) -> Array2<f32> {
+        (**self).t_matmul(rhs)
+    }
+}
+
+// PPMI 行列など、密行列をそのまま単語ベクトルとして扱う
+impl WordMatrix for Array2<f32> {
+    fn shape(&self) -> (usize, usize) {
+        self.dim()
+    }
+
+    fn row(&self, word_id: WordId) -> CowArray<'_, f32, Ix1> {
+        CowArray::from(self.row(word_id))
+    }
+
+    fn matmul(&self, rhs: &Array2<f32>) -> Array2<f32> {
+        self.dot(rhs)
+    }
+
+    fn t_matmul(&self, rhs: &Array2<f32>) -> Array2<f32> {
+        self.t().dot(rhs)
+    }
 }
@@ -1,6 +1,6 @@
-use ndarray::{Array2, ArrayView2};
+use ndarray::{Array2, CowArray, Ix1};
 
-use crate::corpus::Corpus;
+use crate::corpus::{window::Window, Corpus, WordId};
 
 use super::WordMatrix;
 
@@ -8,37 +8,32 @@ pub struct CoMatrix(Array2<f32>);
 
 impl CoMatrix {
     pub fn new(corpus: &Corpus, window_size: usize) -> Self {
+        Self::with_window(corpus, &Window::symmetric(window_size))
+    }
+
+    pub fn with_window(corpus: &Corpus, window: &Window) -> Self {
         let vocab_size = corpus.id_to_word.len();
         let mut matrix = Array2::<f32>::zeros((vocab_size, vocab_size));
 
-        for (idx, word_id) in corpus.text.iter().enumerate() {
-            for i in 1..=window_size {
-                let left_idx = idx.checked_sub(i);
-                let right_idx = if idx + i < corpus.text.len() {
-                    Some(idx + i)
-                } else {
-                    None
-                };
-
-                if let Some(left_idx) = left_idx {
-                    let left_word_id = corpus.text[left_idx];
-                    matrix[[*word_id, left_word_id]] += 1.;
-                }
-
-                if let Some(right_idx) = right_idx {
-                    let right_word_id = corpus.text[right_idx];
-                    matrix[[*word_id, right_word_id]] += 1.;
-                }
-            }
+        for (word_id, context_id, weight) in corpus.co_occurrences(window) {
+            matrix[[word_id, context_id]] += weight;
         }
 
         Self(matrix)
     }
+
+    pub fn matrix(&self) -> &Array2<f32> {
+        &self.0
+    }
 }
 
 impl WordMatrix for CoMatrix {
-    fn view(&self) -> ArrayView2<f32> {
-        self.0.view()
+    fn shape(&self) -> (usize, usize) {
+        self.0.dim()
+    }
+
+    fn row(&self, word_id: WordId) -> CowArray<'_, f32, Ix1> {
+        CowArray::from(self.0.row(word_id))
     }
 }
 
@@ -78,6 +73,8 @@ impl CoMatrix {
 mod tests {
     use ndarray::array;
 
+    use crate::corpus::window::{WindowDirection, WindowWeighting};
+
     use super::*;
 
     #[test]
@@ -100,6 +97,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_co_matrix_with_window() {
+        let text = "You say goodbye and I say hello.";
+        let corpus = Corpus::new(text);
+        let window = Window {
+            window_size: 2,
+            direction: WindowDirection::Left,
+            weighting: WindowWeighting::Harmonic,
+            sentence_boundary: None,
+        };
+        let co_matrix = CoMatrix::with_window(&corpus, &window);
+
+        assert_eq!(
+            co_matrix.0,
+            array![
+                [0., 0., 0., 0., 0., 0., 0.],  // you
+                [1., 0., 0., 0.5, 1., 0., 0.], // say
+                [0.5, 1., 0., 0., 0., 0., 0.], // goodbye
+                [0., 0.5, 1., 0., 0., 0., 0.], // and
+                [0., 0., 0.5, 1., 0., 0., 0.], // i
+                [0., 1., 0., 0., 0.5, 0., 0.], // hello
+                [0., 0.5, 0., 0., 0., 1., 0.], // .
+            ]
+        );
+    }
+
     #[test]
     fn test_ppmi() {
         let text = "You say goodbye and I say hello.";
@@ -0,0 +1,224 @@
+/*
+    CSR (Compressed Sparse Row) 形式の疎行列
+
+    i 行目の非ゼロ要素は
+    indices[indptr[i]..indptr[i + 1]] 列目に data[indptr[i]..indptr[i + 1]] の値を持つ
+*/
+
+use std::collections::HashMap;
+
+use ndarray::{Array1, Array2, CowArray, Ix1};
+
+use crate::corpus::WordId;
+
+use super::WordMatrix;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsrMatrix {
+    shape: (usize, usize),
+    indptr: Vec<usize>,
+    indices: Vec<usize>,
+    data: Vec<f32>,
+}
+
+impl CsrMatrix {
+    // 行ごとの (列番号 -> 値) の対応から CSR 形式の行列を作る
+    pub(crate) fn from_rows(rows: Vec<HashMap<usize, f32>>, number_of_columns: usize) -> Self {
+        let number_of_rows = rows.len();
+        let nnz = rows.iter().map(|row| row.len()).sum();
+
+        let mut indptr = Vec::with_capacity(number_of_rows + 1);
+        let mut indices = Vec::with_capacity(nnz);
+        let mut data = Vec::with_capacity(nnz);
+
+        indptr.push(0);
+        for row in rows {
+            let mut row = row.into_iter().collect::<Vec<_>>();
+            row.sort_by_key(|&(j, _)| j);
+            for (j, value) in row {
+                assert!(j < number_of_columns);
+                indices.push(j);
+                data.push(value);
+            }
+            indptr.push(indices.len());
+        }
+
+        Self {
+            shape: (number_of_rows, number_of_columns),
+            indptr,
+            indices,
+            data,
+        }
+    }
+
+    // 密行列の非ゼロ要素だけを取り出す
+    pub fn from_dense(dense: &Array2<f32>) -> Self {
+        let rows = dense
+            .rows()
+            .into_iter()
+            .map(|row| {
+                row.indexed_iter()
+                    .filter(|&(_, &value)| value != 0.)
+                    .map(|(j, &value)| (j, value))
+                    .collect()
+            })
+            .collect();
+        Self::from_rows(rows, dense.dim().1)
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.shape
+    }
+
+    // 非ゼロ要素の数
+    pub fn nnz(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> f32 {
+        let (start, end) = (self.indptr[i], self.indptr[i + 1]);
+        match self.indices[start..end].binary_search(&j) {
+            Ok(k) => self.data[start + k],
+            Err(_) => 0.,
+        }
+    }
+
+    // i 行目の非ゼロ要素を (列番号, 値) の組で列挙する
+    pub fn row_entries(&self, i: usize) -> impl Iterator<Item = (usize, f32)> + '_ {
+        let (start, end) = (self.indptr[i], self.indptr[i + 1]);
+        self.indices[start..end]
+            .iter()
+            .copied()
+            .zip(self.data[start..end].iter().copied())
+    }
+
+    // すべての非ゼロ要素を (行番号, 列番号, 値) の組で列挙する
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, f32)> + '_ {
+        (0..self.shape.0)
+            .flat_map(move |i| self.row_entries(i).map(move |(j, value)| (i, j, value)))
+    }
+
+    pub fn sum_axis_one(&self) -> Array1<f32> {
+        (0..self.shape.0)
+            .map(|i| self.row_entries(i).map(|(_, value)| value).sum())
+            .collect()
+    }
+
+    pub fn sum_axis_zero(&self) -> Array1<f32> {
+        let mut sum = Array1::zeros(self.shape.1);
+        for (&j, &value) in self.indices.iter().zip(&self.data) {
+            sum[j] += value;
+        }
+        sum
+    }
+
+    pub fn map_values<F>(&self, mut f: F) -> Self
+    where
+        F: FnMut(usize, usize, f32) -> f32,
+    {
+        let rows = (0..self.shape.0)
+            .map(|i| {
+                self.row_entries(i)
+                    .map(|(j, value)| (j, f(i, j, value)))
+                    .filter(|&(_, value)| value != 0.)
+                    .collect::<HashMap<_, _>>()
+            })
+            .collect();
+        Self::from_rows(rows, self.shape.1)
+    }
+
+    pub fn to_dense(&self) -> Array2<f32> {
+        let mut dense = Array2::zeros(self.shape);
+        for (i, j, value) in self.iter() {
+            dense[[i, j]] = value;
+        }
+        dense
+    }
+}
+
+impl WordMatrix for CsrMatrix {
+    fn shape(&self) -> (usize, usize) {
+        self.shape
+    }
+
+    // 非ゼロ要素を密な配列に展開するので O(列数) のコストがかかる
+    // 非ゼロ要素だけを扱う場合は row_entries を使う
+    fn row(&self, word_id: WordId) -> CowArray<'_, f32, Ix1> {
+        let mut row = Array1::zeros(self.shape.1);
+        for (j, value) in self.row_entries(word_id) {
+            row[j] = value;
+        }
+        CowArray::from(row)
+    }
+
+    fn row_norms(&self) -> Array1<f32> {
+        (0..self.shape.0)
+            .map(|i| {
+                self.row_entries(i)
+                    .map(|(_, value)| value * value)
+                    .sum::<f32>()
+                    .sqrt()
+            })
+            .collect()
+    }
+
+    // 非ゼロ要素についてだけ積和を取る
+    fn matmul(&self, rhs: &Array2<f32>) -> Array2<f32> {
+        assert_eq!(self.shape.1, rhs.dim().0);
+        let mut out = Array2::zeros((self.shape.0, rhs.dim().1));
+        for (i, j, value) in self.iter() {
+            out.row_mut(i).scaled_add(value, &rhs.row(j));
+        }
+        out
+    }
+
+    fn t_matmul(&self, rhs: &Array2<f32>) -> Array2<f32> {
+        assert_eq!(self.shape.0, rhs.dim().0);
+        let mut out = Array2::zeros((self.shape.1, rhs.dim().1));
+        for (i, j, value) in self.iter() {
+            out.row_mut(j).scaled_add(value, &rhs.row(i));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn test_csr_matrix() {
+        let rows = vec![
+            HashMap::from([(2, 3.), (0, 1.)]),
+            HashMap::new(),
+            HashMap::from([(1, 5.)]),
+        ];
+        let csr = CsrMatrix::from_rows(rows, 4);
+
+        assert_eq!(csr.dim(), (3, 4));
+        assert_eq!(csr.nnz(), 3);
+        assert_eq!(csr.indptr, vec![0, 2, 2, 3]);
+        assert_eq!(csr.indices, vec![0, 2, 1]);
+        assert_eq!(csr.data, vec![1., 3., 5.]);
+
+        assert_eq!(csr.get(0, 2), 3.);
+        assert_eq!(csr.get(1, 2), 0.);
+        assert_eq!(
+            csr.to_dense(),
+            array![[1., 0., 3., 0.], [0., 0., 0., 0.], [0., 5., 0., 0.]]
+        );
+        assert_eq!(csr.sum_axis_one(), array![4., 0., 5.]);
+        assert_eq!(csr.sum_axis_zero(), array![1., 5., 3., 0.]);
+        assert_eq!(WordMatrix::row(&csr, 2).to_owned(), array![0., 5., 0., 0.]);
+        assert_eq!(CsrMatrix::from_dense(&csr.to_dense()), csr);
+        assert_eq!(csr.row_norms(), csr.to_dense().row_norms());
+        assert_eq!(csr.row_norms(), array![10_f32.sqrt(), 0., 5.]);
+
+        let rhs = array![[1., 2.], [3., 4.], [5., 6.], [7., 8.]];
+        assert_eq!(csr.matmul(&rhs), csr.to_dense().dot(&rhs));
+        let rhs = array![[1., 2.], [3., 4.], [5., 6.]];
+        assert_eq!(csr.t_matmul(&rhs), csr.to_dense().t().dot(&rhs));
+    }
+}
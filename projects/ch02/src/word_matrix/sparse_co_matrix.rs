@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use ndarray::{Array1, Array2, CowArray, Ix1};
+
+use crate::corpus::{window::Window, Corpus, WordId};
+
+use super::{csr_matrix::CsrMatrix, WordMatrix};
+
+// 共起行列のうち非ゼロ要素だけを保持する
+// 語彙数が大きいコーパスでは CoMatrix の代わりに用いる
+pub struct SparseCoMatrix(CsrMatrix);
+
+impl SparseCoMatrix {
+    pub fn new(corpus: &Corpus, window_size: usize) -> Self {
+        Self::with_window(corpus, &Window::symmetric(window_size))
+    }
+
+    pub fn with_window(corpus: &Corpus, window: &Window) -> Self {
+        let vocab_size = corpus.id_to_word.len();
+        let mut rows = vec![HashMap::<WordId, f32>::new(); vocab_size];
+
+        for (word_id, context_id, weight) in corpus.co_occurrences(window) {
+            *rows[word_id].entry(context_id).or_insert(0.) += weight;
+        }
+
+        Self(CsrMatrix::from_rows(rows, vocab_size))
+    }
+
+    pub fn matrix(&self) -> &CsrMatrix {
+        &self.0
+    }
+}
+
+impl WordMatrix for SparseCoMatrix {
+    fn shape(&self) -> (usize, usize) {
+        self.0.shape()
+    }
+
+    fn row(&self, word_id: WordId) -> CowArray<'_, f32, Ix1> {
+        self.0.row(word_id)
+    }
+
+    fn row_norms(&self) -> Array1<f32> {
+        self.0.row_norms()
+    }
+
+    fn matmul(&self, rhs: &Array2<f32>) -> Array2<f32> {
+        self.0.matmul(rhs)
+    }
+
+    fn t_matmul(&self, rhs: &Array2<f32>) -> Array2<f32> {
+        self.0.t_matmul(rhs)
+    }
+}
+
+impl SparseCoMatrix {
+    // CoMatrix::ppmi と同じ値を、共起回数が非ゼロの要素についてだけ計算する
+    // （共起回数が０の要素の PPMI は常に０）
+    pub fn ppmi(&self, verbose: bool, eps: Option<f32>) -> CsrMatrix {
+        let eps = eps.unwrap_or(1e-8);
+        let mut cnt = 0;
+        let total = self.0.nnz();
+
+        let row_sum = self.0.sum_axis_one();
+        let col_sum = self.0.sum_axis_zero();
+        let sum = row_sum.sum();
+
+        self.0.map_values(|i, j, value| {
+            let pmi = (value * sum) / (row_sum[i] * col_sum[j]);
+
+            if verbose {
+                cnt += 1;
+                if cnt % 100_000 == 0 {
+                    println!("PPMI: {}/{}", cnt, total);
+                }
+            }
+
+            if pmi > 0. {
+                (pmi + eps).log2()
+            } else {
+                0.
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        corpus::window::{WindowDirection, WindowWeighting},
+        word_matrix::co_matrix::CoMatrix,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_sparse_co_matrix() {
+        let text = "You say goodbye and I say hello.";
+        let corpus = Corpus::new(text);
+        let co_matrix = CoMatrix::new(&corpus, 2);
+        let sparse_co_matrix = SparseCoMatrix::new(&corpus, 2);
+
+        assert_eq!(sparse_co_matrix.shape(), (7, 7));
+        for word_id in 0..7 {
+            assert_eq!(sparse_co_matrix.row(word_id), co_matrix.row(word_id));
+        }
+
+        let window = Window {
+            window_size: 2,
+            direction: WindowDirection::Right,
+            weighting: WindowWeighting::Harmonic,
+            sentence_boundary: Some(".".to_string()),
+        };
+        let co_matrix = CoMatrix::with_window(&corpus, &window);
+        let sparse_co_matrix = SparseCoMatrix::with_window(&corpus, &window);
+        for word_id in 0..7 {
+            assert_eq!(sparse_co_matrix.row(word_id), co_matrix.row(word_id));
+        }
+    }
+
+    #[test]
+    fn test_sparse_ppmi() {
+        let text = "You say goodbye and I say hello.";
+        let corpus = Corpus::new(text);
+        let ppmi = CoMatrix::new(&corpus, 2).ppmi(false, None);
+        let sparse_ppmi = SparseCoMatrix::new(&corpus, 2).ppmi(false, None);
+
+        assert_eq!(sparse_ppmi.to_dense(), ppmi);
+        // PPMI が０となる要素は保持しない
+        assert_eq!(
+            sparse_ppmi.nnz(),
+            ppmi.iter().filter(|&&value| value != 0.).count()
+        );
+    }
+}
@@ -0,0 +1,185 @@
+/*
+    M = U S V^T (特異値分解)
+    U の先頭 dim 列を、各単語の dim 次元の単語ベクトルとして用いる
+
+    乱択 SVD (Halko et al., 2011)
+    1. ランダム行列 Ω (語彙数 × (dim + n_oversamples)) に対して Y = M Ω を計算
+    2. (M M^T)^n_iter Y を QR 分解して M の列空間を近似する正規直交基底 Q を得る
+    3. 小さな行列 B = Q^T M を特異値分解して B = U_B S V^T
+    4. U = Q U_B
+*/
+
+use ndarray::{s, Array1, Array2, CowArray, Ix1};
+use ndarray_linalg::{QR, SVD};
+use ndarray_rand::{rand_distr::StandardNormal, RandomExt};
+use rand::Rng;
+
+use crate::corpus::WordId;
+
+use super::WordMatrix;
+
+pub struct SvdMatrix {
+    word_vectors: Array2<f32>,
+    singular_values: Array1<f32>,
+}
+
+pub struct InitParamsOfRandomizedSvd {
+    pub dim: usize,
+    // 近似精度を上げるために dim に追加して求める基底の数
+    pub n_oversamples: usize,
+    // べき乗反復の回数
+    pub n_iter: usize,
+}
+
+impl SvdMatrix {
+    // 密行列をそのまま特異値分解する
+    pub fn full(matrix: &Array2<f32>, dim: usize) -> Self {
+        assert!(dim <= matrix.dim().0.min(matrix.dim().1));
+
+        let (u, s, _) = matrix.svd(true, false).unwrap();
+        let u = u.unwrap();
+
+        Self {
+            word_vectors: u.slice(s![.., ..dim]).to_owned(),
+            singular_values: s.slice(s![..dim]).to_owned(),
+        }
+    }
+
+    // 行列積だけを用いて上位 dim 個の特異値・特異ベクトルを近似的に求める
+    pub fn randomized<T: WordMatrix, R: Rng>(
+        matrix: &T,
+        params: InitParamsOfRandomizedSvd,
+        rng: &mut R,
+    ) -> Self {
+        let InitParamsOfRandomizedSvd {
+            dim,
+            n_oversamples,
+            n_iter,
+        } = params;
+
+        let (vocab_size, width) = matrix.shape();
+        let rank = (dim + n_oversamples).min(vocab_size).min(width);
+        assert!(dim <= rank);
+
+        let omega = Array2::<f32>::random_using((width, rank), StandardNormal, rng);
+        let mut y = matrix.matmul(&omega);
+
+        // べき乗反復（数値誤差を抑えるため、各段階で正規直交化する）
+        for _ in 0..n_iter {
+            let (q, _) = y.qr().unwrap();
+            let (z, _) = matrix.t_matmul(&q).qr().unwrap();
+            y = matrix.matmul(&z);
+        }
+
+        let (q, _) = y.qr().unwrap();
+
+        // B = Q^T M = (M^T Q)^T
+        let b = matrix.t_matmul(&q).reversed_axes();
+        let (u_b, s, _) = b.svd(true, false).unwrap();
+        let u = q.dot(&u_b.unwrap());
+
+        Self {
+            word_vectors: u.slice(s![.., ..dim]).to_owned(),
+            singular_values: s.slice(s![..dim]).to_owned(),
+        }
+    }
+
+    pub fn singular_values(&self) -> &Array1<f32> {
+        &self.singular_values
+    }
+}
+
+impl WordMatrix for SvdMatrix {
+    fn shape(&self) -> (usize, usize) {
+        self.word_vectors.dim()
+    }
+
+    fn row(&self, word_id: WordId) -> CowArray<'_, f32, Ix1> {
+        CowArray::from(self.word_vectors.row(word_id))
+    }
+
+    fn matmul(&self, rhs: &Array2<f32>) -> Array2<f32> {
+        self.word_vectors.dot(rhs)
+    }
+
+    fn t_matmul(&self, rhs: &Array2<f32>) -> Array2<f32> {
+        self.word_vectors.t().dot(rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use ndarray::array;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{
+        corpus::Corpus,
+        word_matrix::{co_matrix::CoMatrix, sparse_co_matrix::SparseCoMatrix},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_full_svd() {
+        let matrix = array![[0., 2., 0.], [3., 0., 0.], [0., 0., 0.], [0., 0., 1.]];
+        let svd = SvdMatrix::full(&matrix, 2);
+
+        assert_eq!(svd.shape(), (4, 2));
+        svd.singular_values()
+            .iter()
+            .zip([3., 2.])
+            .for_each(|(&actual, expected)| {
+                assert_abs_diff_eq!(actual, expected, epsilon = 1e-5);
+            });
+
+        // 特異ベクトルの符号は一意に定まらないので絶対値で比較する
+        let expected = array![[0., 1.], [1., 0.], [0., 0.], [0., 0.]];
+        svd.word_vectors
+            .iter()
+            .zip(expected.iter())
+            .for_each(|(actual, expected)| {
+                assert_abs_diff_eq!(actual.abs(), expected, epsilon = 1e-5);
+            });
+    }
+
+    #[test]
+    fn test_randomized_svd() {
+        let text = "You say goodbye and I say hello.";
+        let corpus = Corpus::new(text);
+        let ppmi = CoMatrix::new(&corpus, 1).ppmi(false, None);
+        let sparse_ppmi = SparseCoMatrix::new(&corpus, 1).ppmi(false, None);
+
+        // 特異値は重複するので、第２・第３特異値の間で区切る
+        let full = SvdMatrix::full(&ppmi, 2);
+        // 語彙数と同じだけ基底を取れば、近似なしに列空間を表現できる
+        let params = || InitParamsOfRandomizedSvd {
+            dim: 2,
+            n_oversamples: 5,
+            n_iter: 2,
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+        let dense = SvdMatrix::randomized(&ppmi, params(), &mut rng);
+        let sparse = SvdMatrix::randomized(&sparse_ppmi, params(), &mut rng);
+
+        for randomized in [dense, sparse] {
+            randomized
+                .singular_values()
+                .iter()
+                .zip(full.singular_values())
+                .for_each(|(actual, expected)| {
+                    assert_abs_diff_eq!(actual, expected, epsilon = 1e-4);
+                });
+
+            // 特異ベクトルの符号によらない U U^T で比較する
+            let actual = randomized.word_vectors.dot(&randomized.word_vectors.t());
+            let expected = full.word_vectors.dot(&full.word_vectors.t());
+            actual
+                .iter()
+                .zip(expected.iter())
+                .for_each(|(actual, expected)| {
+                    assert_abs_diff_eq!(actual, expected, epsilon = 1e-4);
+                });
+        }
+    }
+}
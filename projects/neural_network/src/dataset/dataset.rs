@@ -20,3 +20,17 @@ where
     pub bundled_one_hot_labels: M2,
     pub ph: PhantomData<M1>,
 }
+
+// 言語モデルなどの時系列データのデータセット
+// 隠れ状態をミニバッチ間で引き継ぐので、シャッフルせずに先頭から順に取り出す
+pub trait TimeSeriesDataset: ExactSizeIterator<Item = TimeSeriesMiniBatch> {
+    fn reset_cursor(&mut self);
+    // テスト用データを順に並べたミニバッチ
+    fn test_data(&self) -> Vec<TimeSeriesMiniBatch>;
+}
+
+// inputs[n][t], labels[n][t]: バッチの n 番目の系列の時刻 t の ID
+pub struct TimeSeriesMiniBatch {
+    pub inputs: Vec<Vec<usize>>,
+    pub labels: Vec<Vec<usize>>,
+}
@@ -0,0 +1,104 @@
+/*
+    Y = W[idx]  (埋め込み行列 W から idx[n] 番目の行を取り出して並べたもの)
+    ∂L/∂W[idx[n]] += ∂L/∂Y[n]  (同じ ID が複数回現れる場合は足し合わせる)
+*/
+
+use std::marker::PhantomData;
+
+use crate::matrix::{matrix_one_dim::MatrixOneDim, matrix_two_dim::MatrixTwoDim};
+
+use super::layer::Layer;
+
+pub(crate) struct Embedding<M2, M1> {
+    idx: Option<Vec<usize>>,
+    w_dim: Option<(usize, usize)>,
+    ph: PhantomData<(M2, M1)>,
+}
+
+pub(crate) struct InputOfEmbeddingLayer<M2> {
+    pub(crate) idx: Vec<usize>,
+    pub(crate) w: M2,
+}
+
+pub(crate) struct DInputOfEmbeddingLayer<M2> {
+    pub(crate) dw: M2,
+}
+
+pub(crate) struct OutputOfEmbeddingLayer<M2> {
+    out: M2,
+}
+
+impl<M2> OutputOfEmbeddingLayer<M2> {
+    pub fn into_value(self) -> M2 {
+        self.out
+    }
+}
+
+impl<M2> From<M2> for OutputOfEmbeddingLayer<M2> {
+    fn from(value: M2) -> Self {
+        Self { out: value }
+    }
+}
+
+impl<M2, M1> Layer<M2, M1> for Embedding<M2, M1>
+where
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    type Input = InputOfEmbeddingLayer<M2>;
+    type Output = OutputOfEmbeddingLayer<M2>;
+    type DInput = DInputOfEmbeddingLayer<M2>;
+
+    fn new() -> Self {
+        Self {
+            idx: None,
+            w_dim: None,
+            ph: PhantomData,
+        }
+    }
+
+    fn forward(&mut self, input: Self::Input) -> Self::Output {
+        let Self::Input { idx, w } = input;
+        let out = w.select_rows(&idx);
+        self.idx = Some(idx);
+        self.w_dim = Some(w.dim());
+        Self::Output { out }
+    }
+
+    fn backward(&self, dout: Self::Output) -> Self::DInput {
+        assert!(self.idx.is_some());
+        assert!(self.w_dim.is_some());
+        let idx = self.idx.as_ref().unwrap();
+        let Self::Output { out: dout } = dout;
+
+        let mut dw = M2::zeros(self.w_dim.unwrap());
+        dw.add_to_rows(idx, &dout);
+        Self::DInput { dw }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Array1, Array2};
+
+    use super::*;
+
+    #[test]
+    fn test_embedding_layer() {
+        // test forward
+        let mut embedding = Embedding::<Array2<f32>, Array1<f32>>::new();
+        let input = InputOfEmbeddingLayer {
+            idx: vec![2, 0, 2],
+            w: array![[1., 2.], [3., 4.], [5., 6.]],
+        };
+        let output = embedding.forward(input);
+        assert_eq!(output.out, array![[5., 6.], [1., 2.], [5., 6.]]);
+
+        // test backward
+        let dout = OutputOfEmbeddingLayer {
+            out: array![[7., 8.], [9., 10.], [11., 12.]],
+        };
+        let dinput = embedding.backward(dout);
+        assert_eq!(dinput.dw, array![[9., 10.], [0., 0.], [18., 20.]]);
+    }
+}
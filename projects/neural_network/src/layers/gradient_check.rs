@@ -0,0 +1,123 @@
+/*
+    逆誤差伝播法で求めた勾配を、中心差分による数値微分と比較するためのテスト用の関数
+    ∂f/∂x_i ≈ (f(x + δe_i) - f(x - δe_i)) / 2δ
+
+    RNN・LSTM・GRU の時系列レイヤに共通するテスト
+    (バッチサイズ 2, 時刻 4, 入力の次元 3) の入力と、隠れ状態の次元 2 のレイヤを用いる
+*/
+
+use approx::assert_abs_diff_eq;
+use ndarray::{array, Array, Array1, Array2, Array3, Dimension};
+
+use crate::{
+    matrix::matrix_three_dim::MatrixThreeDim,
+    network::layers::layer::{LayerBase, TimeIntermediateLayer},
+};
+
+// f32 の精度では δ を小さくしすぎると丸め誤差の方が大きくなる
+const DELTA: f32 = 1e-2;
+const EPSILON: f32 = 1e-3;
+
+pub(crate) fn numerical_gradient<D, F>(x: &Array<f32, D>, mut f: F) -> Array<f32, D>
+where
+    D: Dimension,
+    F: FnMut(Array<f32, D>) -> f32,
+{
+    let gradient = (0..x.len())
+        .map(|i| {
+            let mut plus = x.clone();
+            *plus.iter_mut().nth(i).unwrap() += DELTA;
+            let mut minus = x.clone();
+            *minus.iter_mut().nth(i).unwrap() -= DELTA;
+            (f(plus) - f(minus)) / (2. * DELTA)
+        })
+        .collect();
+    Array::from_shape_vec(x.raw_dim(), gradient).unwrap()
+}
+
+pub(crate) fn assert_gradient_eq<D: Dimension>(actual: &Array<f32, D>, expected: &Array<f32, D>) {
+    assert_eq!(actual.shape(), expected.shape());
+    for (actual, expected) in actual.iter().zip(expected) {
+        assert_abs_diff_eq!(actual, expected, epsilon = EPSILON);
+    }
+}
+
+pub(crate) fn time_xs() -> Array3<f32> {
+    Array3::from_time_slices(vec![
+        array![[0.1, -0.2, 0.3], [0.4, 0.5, -0.6]],
+        array![[-0.3, 0.2, 0.1], [0.0, -0.5, 0.2]],
+        array![[0.6, 0.1, -0.2], [-0.1, 0.3, 0.4]],
+        array![[0.2, 0.2, 0.2], [0.5, -0.1, -0.3]],
+    ])
+}
+
+// ４時刻を一度に流した場合と、２時刻ずつ２回に分けて流した場合で隠れ状態が一致し、
+// 状態をリセットすると最初からやり直すことを確かめる
+pub(crate) fn assert_stateful<L>(params: L::Params)
+where
+    L: TimeIntermediateLayer<Array3<f32>, Array2<f32>, Array1<f32>>,
+    L::Params: Clone,
+{
+    let mut layer = L::new(params.clone());
+    let expected = layer.forward(time_xs());
+
+    let mut layer = L::new(params);
+    let former = layer.forward(time_xs().slice_time(0..2));
+    let latter = layer.forward(time_xs().slice_time(2..4));
+    assert_eq!(Array3::concat_time(&[former, latter]), expected);
+
+    layer.reset_state();
+    assert_eq!(layer.forward(time_xs()), expected);
+}
+
+// L = Σ dout * H として、入力とパラメータ (Wx, Wh, B) に関する微分を数値計算と比較する
+// weights はパラメータから (Wx, Wh, B) を取り出す
+pub(crate) fn assert_time_gradients<L, F>(params: L::Params, weights: F)
+where
+    L: TimeIntermediateLayer<Array3<f32>, Array2<f32>, Array1<f32>>,
+    L::Params: Clone,
+    F: Fn(&mut L::Params) -> (&mut Array2<f32>, &mut Array2<f32>, &mut Array1<f32>),
+{
+    let dout = Array3::from_time_slices(vec![
+        array![[1., -2.], [0.5, 3.]],
+        array![[-1., 0.5], [2., 1.]],
+        array![[0.3, 0.2], [-0.7, 1.5]],
+        array![[2., 1.], [-1., -0.5]],
+    ]);
+    let loss = |params: L::Params, xs: Array3<f32>| {
+        let mut layer = L::new(params);
+        (layer.forward(xs) * &dout).sum()
+    };
+
+    let mut layer = L::new(params.clone());
+    layer.forward(time_xs());
+    let dxs = layer.backward(dout.clone());
+    let (_, grads) = layer.params_and_grads();
+    let mut grads = grads.clone();
+    let (dwx, dwh, db) = weights(&mut grads);
+
+    let expected = numerical_gradient(&time_xs(), |xs| loss(params.clone(), xs));
+    assert_gradient_eq(&dxs, &expected);
+
+    // パラメータを１つずつ動かして数値微分する
+    let mut initial = params.clone();
+    let (wx, wh, b) = weights(&mut initial);
+    let expected = numerical_gradient(wx, |wx| {
+        let mut params = params.clone();
+        *weights(&mut params).0 = wx;
+        loss(params, time_xs())
+    });
+    assert_gradient_eq(dwx, &expected);
+    let expected = numerical_gradient(wh, |wh| {
+        let mut params = params.clone();
+        *weights(&mut params).1 = wh;
+        loss(params, time_xs())
+    });
+    assert_gradient_eq(dwh, &expected);
+    let expected = numerical_gradient(b, |b| {
+        let mut params = params.clone();
+        *weights(&mut params).2 = b;
+        loss(params, time_xs())
+    });
+    assert_gradient_eq(db, &expected);
+}
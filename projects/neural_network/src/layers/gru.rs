@@ -0,0 +1,232 @@
+/*
+    GRU の１時刻分の計算
+    Wx, Wh, B を列方向に３等分して、それぞれ z, r, h̃ 用の重みとする
+    Z = σ(X.dot(Wx_z) + H_prev.dot(Wh_z) + B_z)            (更新ゲート)
+    R = σ(X.dot(Wx_r) + H_prev.dot(Wh_r) + B_r)            (リセットゲート)
+    H̃ = tanh(X.dot(Wx_h) + (R * H_prev).dot(Wh_h) + B_h)  (新しい隠れ状態の候補)
+    H_next = (1 - Z) * H_prev + Z * H̃
+
+    ∂L/∂A_h = ∂L/∂H_next * Z * (1 - H̃^2)
+    ∂L/∂A_z = ∂L/∂H_next * (H̃ - H_prev) * Z(1 - Z)
+    ∂L/∂A_r = ∂L/∂A_h.dot(Wh_h^T) * H_prev * R(1 - R)
+    ∂L/∂H_prev = ∂L/∂H_next * (1 - Z) + ∂L/∂A_h.dot(Wh_h^T) * R
+                 + ∂L/∂A_z.dot(Wh_z^T) + ∂L/∂A_r.dot(Wh_r^T)
+    ∂L/∂Wh_z = H_prev^T.dot(∂L/∂A_z), ∂L/∂Wh_r = H_prev^T.dot(∂L/∂A_r)
+    ∂L/∂Wh_h = (R * H_prev)^T.dot(∂L/∂A_h)
+    ∂L/∂X, ∂L/∂Wx, ∂L/∂B は A = [A_z, A_r, A_h] に対して Affine と同様
+*/
+
+use std::marker::PhantomData;
+
+use crate::matrix::{matrix_one_dim::MatrixOneDim, matrix_two_dim::MatrixTwoDim};
+
+use super::{layer::Layer, sigmoid::sigmoid};
+
+pub(crate) struct Gru<M2, M1> {
+    x: Option<M2>,
+    h_prev: Option<M2>,
+    wx: Option<M2>,
+    // Wh を [Wh_z, Wh_r, Wh_h] に分けたもの
+    wh: Option<Vec<M2>>,
+    // 各ゲートの値 [Z, R, H̃]
+    gates: Option<Vec<M2>>,
+    ph: PhantomData<M1>,
+}
+
+pub(crate) struct InputOfGruLayer<M2, M1> {
+    pub(crate) x: M2,
+    pub(crate) h_prev: M2,
+    pub(crate) wx: M2,
+    pub(crate) wh: M2,
+    pub(crate) b: M1,
+}
+
+pub(crate) struct DInputOfGruLayer<M2, M1> {
+    pub(crate) dx: M2,
+    pub(crate) dh_prev: M2,
+    pub(crate) dwx: M2,
+    pub(crate) dwh: M2,
+    pub(crate) db: M1,
+}
+
+pub(crate) struct OutputOfGruLayer<M2> {
+    h_next: M2,
+}
+
+impl<M2> OutputOfGruLayer<M2> {
+    pub fn into_value(self) -> M2 {
+        self.h_next
+    }
+}
+
+impl<M2> From<M2> for OutputOfGruLayer<M2> {
+    fn from(value: M2) -> Self {
+        Self { h_next: value }
+    }
+}
+
+impl<M2, M1> Layer<M2, M1> for Gru<M2, M1>
+where
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    type Input = InputOfGruLayer<M2, M1>;
+    type Output = OutputOfGruLayer<M2>;
+    type DInput = DInputOfGruLayer<M2, M1>;
+
+    fn new() -> Self {
+        Self {
+            x: None,
+            h_prev: None,
+            wx: None,
+            wh: None,
+            gates: None,
+            ph: PhantomData,
+        }
+    }
+
+    fn forward(&mut self, input: Self::Input) -> Self::Output {
+        let Self::Input {
+            x,
+            h_prev,
+            wx,
+            wh,
+            b,
+        } = input;
+        let ax = (x.dot(&wx) + b).split_columns(3);
+        let wh = wh.split_columns(3);
+
+        let z = (ax[0].clone() + h_prev.dot(&wh[0])).mapv_into(sigmoid);
+        let r = (ax[1].clone() + h_prev.dot(&wh[1])).mapv_into(sigmoid);
+        let h_tilde =
+            (ax[2].clone() + (r.clone() * h_prev.clone()).dot(&wh[2])).mapv_into(|x| x.tanh());
+        let h_next = z.clone().mapv_into(|z| 1. - z) * h_prev.clone() + z.clone() * h_tilde.clone();
+
+        self.x = Some(x);
+        self.h_prev = Some(h_prev);
+        self.wx = Some(wx);
+        self.wh = Some(wh);
+        self.gates = Some(vec![z, r, h_tilde]);
+        Self::Output { h_next }
+    }
+
+    fn backward(&self, dout: Self::Output) -> Self::DInput {
+        assert!(self.x.is_some());
+        assert!(self.gates.is_some());
+        let x = self.x.as_ref().unwrap();
+        let h_prev = self.h_prev.as_ref().unwrap();
+        let wx = self.wx.as_ref().unwrap();
+        let wh = self.wh.as_ref().unwrap();
+        let (z, r, h_tilde) = match self.gates.as_deref().unwrap() {
+            [z, r, h_tilde] => (z, r, h_tilde),
+            _ => unreachable!(),
+        };
+        let Self::Output { h_next: dh_next } = dout;
+
+        let sigmoid_grad = |y: &M2| y.clone() * y.clone().mapv_into(|y| 1. - y);
+        let da_h = dh_next.clone() * z.clone() * h_tilde.clone().mapv_into(|h| 1. - h * h);
+        let da_z = dh_next.clone() * (h_tilde.clone() - h_prev.clone()) * sigmoid_grad(z);
+        // (R * H_prev) に関する勾配
+        let drh = da_h.dot(&wh[2].t());
+        let da_r = drh.clone() * h_prev.clone() * sigmoid_grad(r);
+
+        let dh_prev = dh_next * z.clone().mapv_into(|z| 1. - z)
+            + drh * r.clone()
+            + da_z.dot(&wh[0].t())
+            + da_r.dot(&wh[1].t());
+        let dwh = M2::concat_columns(&[
+            h_prev.t().dot(&da_z),
+            h_prev.t().dot(&da_r),
+            (r.clone() * h_prev.clone()).t().dot(&da_h),
+        ]);
+        let da = M2::concat_columns(&[da_z, da_r, da_h]);
+
+        Self::DInput {
+            dx: da.dot(&wx.t()),
+            dh_prev,
+            dwx: x.t().dot(&da),
+            dwh,
+            db: da.sum_axis_zero(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use ndarray::{array, Array1, Array2};
+
+    use crate::layers::gradient_check::{assert_gradient_eq, numerical_gradient};
+
+    use super::*;
+
+    fn input() -> InputOfGruLayer<Array2<f32>, Array1<f32>> {
+        InputOfGruLayer {
+            x: array![[0.1, -0.2, 0.3], [0.4, 0.5, -0.6]],
+            h_prev: array![[0.2, -0.1], [-0.3, 0.4]],
+            wx: array![
+                [0.5, -0.4, 0.2, 0.1, -0.3, 0.6],
+                [0.3, 0.2, -0.5, 0.4, 0.1, -0.1],
+                [-0.1, 0.6, 0.3, -0.2, 0.5, 0.2]
+            ],
+            wh: array![
+                [0.7, -0.2, 0.1, 0.3, -0.4, 0.2],
+                [0.1, 0.3, -0.2, 0.6, 0.2, -0.5]
+            ],
+            b: array![0.05, -0.05, 0.1, 0., -0.1, 0.2],
+        }
+    }
+
+    #[test]
+    fn test_gru_forward() {
+        let mut gru = Gru::new();
+        let output = gru.forward(input());
+
+        // 第１行目の１番目の隠れ状態を直接計算する
+        let InputOfGruLayer {
+            x,
+            h_prev,
+            wx,
+            wh,
+            b,
+        } = input();
+        let ax = x.dot(&wx) + b;
+        let (h0, h1) = (h_prev[[0, 0]], h_prev[[0, 1]]);
+        let z = sigmoid(ax[[0, 0]] + h0 * wh[[0, 0]] + h1 * wh[[1, 0]]);
+        let r0 = sigmoid(ax[[0, 2]] + h0 * wh[[0, 2]] + h1 * wh[[1, 2]]);
+        let r1 = sigmoid(ax[[0, 3]] + h0 * wh[[0, 3]] + h1 * wh[[1, 3]]);
+        let h_tilde = (ax[[0, 4]] + r0 * h0 * wh[[0, 4]] + r1 * h1 * wh[[1, 4]]).tanh();
+        assert_abs_diff_eq!(
+            output.h_next[[0, 0]],
+            (1. - z) * h0 + z * h_tilde,
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    fn test_gru_backward() {
+        // L = Σ dout * H_next として、各入力に関する微分を数値計算と比較する
+        let dout = array![[1., -2.], [0.5, 3.]];
+        let loss = |input: InputOfGruLayer<Array2<f32>, Array1<f32>>| {
+            (Gru::new().forward(input).h_next * &dout).sum()
+        };
+
+        let mut gru = Gru::new();
+        let _ = gru.forward(input());
+        let dinput = gru.backward(OutputOfGruLayer::from(dout.clone()));
+
+        let base = input();
+        let expected = numerical_gradient(&base.x, |x| loss(InputOfGruLayer { x, ..input() }));
+        assert_gradient_eq(&dinput.dx, &expected);
+        let expected = numerical_gradient(&base.h_prev, |h_prev| {
+            loss(InputOfGruLayer { h_prev, ..input() })
+        });
+        assert_gradient_eq(&dinput.dh_prev, &expected);
+        let expected = numerical_gradient(&base.wx, |wx| loss(InputOfGruLayer { wx, ..input() }));
+        assert_gradient_eq(&dinput.dwx, &expected);
+        let expected = numerical_gradient(&base.wh, |wh| loss(InputOfGruLayer { wh, ..input() }));
+        assert_gradient_eq(&dinput.dwh, &expected);
+        let expected = numerical_gradient(&base.b, |b| loss(InputOfGruLayer { b, ..input() }));
+        assert_gradient_eq(&dinput.db, &expected);
+    }
+}
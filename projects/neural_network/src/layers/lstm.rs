@@ -0,0 +1,251 @@
+/*
+    LSTM の１時刻分の計算
+    A = X.dot(Wx) + H_prev.dot(Wh) + B  (列方向に４等分して [A_f, A_g, A_i, A_o] とする)
+    F = σ(A_f)    (忘却ゲート)
+    G = tanh(A_g) (記憶セルに加える新しい情報)
+    I = σ(A_i)    (入力ゲート)
+    O = σ(A_o)    (出力ゲート)
+    C_next = F * C_prev + G * I
+    H_next = O * tanh(C_next)
+
+    S = ∂L/∂C_next + ∂L/∂H_next * O * (1 - tanh(C_next)^2)
+    ∂L/∂C_prev = S * F
+    ∂L/∂A_f = S * C_prev * F(1 - F)
+    ∂L/∂A_g = S * I * (1 - G^2)
+    ∂L/∂A_i = S * G * I(1 - I)
+    ∂L/∂A_o = ∂L/∂H_next * tanh(C_next) * O(1 - O)
+    以降は Affine と同様に ∂L/∂A から ∂L/∂X, ∂L/∂H_prev, ∂L/∂Wx, ∂L/∂Wh, ∂L/∂B を求める
+*/
+
+use std::marker::PhantomData;
+
+use crate::matrix::{matrix_one_dim::MatrixOneDim, matrix_two_dim::MatrixTwoDim};
+
+use super::{layer::Layer, sigmoid::sigmoid};
+
+pub(crate) struct Lstm<M2, M1> {
+    x: Option<M2>,
+    h_prev: Option<M2>,
+    c_prev: Option<M2>,
+    wx: Option<M2>,
+    wh: Option<M2>,
+    // 各ゲートの値 [F, G, I, O]
+    gates: Option<Vec<M2>>,
+    tanh_c_next: Option<M2>,
+    ph: PhantomData<M1>,
+}
+
+pub(crate) struct InputOfLstmLayer<M2, M1> {
+    pub(crate) x: M2,
+    pub(crate) h_prev: M2,
+    pub(crate) c_prev: M2,
+    pub(crate) wx: M2,
+    pub(crate) wh: M2,
+    pub(crate) b: M1,
+}
+
+pub(crate) struct DInputOfLstmLayer<M2, M1> {
+    pub(crate) dx: M2,
+    pub(crate) dh_prev: M2,
+    pub(crate) dc_prev: M2,
+    pub(crate) dwx: M2,
+    pub(crate) dwh: M2,
+    pub(crate) db: M1,
+}
+
+pub(crate) struct OutputOfLstmLayer<M2> {
+    h_next: M2,
+    c_next: M2,
+}
+
+impl<M2> OutputOfLstmLayer<M2> {
+    pub fn into_value(self) -> (M2, M2) {
+        (self.h_next, self.c_next)
+    }
+}
+
+impl<M2> From<(M2, M2)> for OutputOfLstmLayer<M2> {
+    fn from((h_next, c_next): (M2, M2)) -> Self {
+        Self { h_next, c_next }
+    }
+}
+
+impl<M2, M1> Layer<M2, M1> for Lstm<M2, M1>
+where
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    type Input = InputOfLstmLayer<M2, M1>;
+    type Output = OutputOfLstmLayer<M2>;
+    type DInput = DInputOfLstmLayer<M2, M1>;
+
+    fn new() -> Self {
+        Self {
+            x: None,
+            h_prev: None,
+            c_prev: None,
+            wx: None,
+            wh: None,
+            gates: None,
+            tanh_c_next: None,
+            ph: PhantomData,
+        }
+    }
+
+    fn forward(&mut self, input: Self::Input) -> Self::Output {
+        let Self::Input {
+            x,
+            h_prev,
+            c_prev,
+            wx,
+            wh,
+            b,
+        } = input;
+        let a = x.dot(&wx) + h_prev.dot(&wh) + b;
+
+        let mut gates = a.split_columns(4).into_iter();
+        let f = gates.next().unwrap().mapv_into(sigmoid);
+        let g = gates.next().unwrap().mapv_into(|x| x.tanh());
+        let i = gates.next().unwrap().mapv_into(sigmoid);
+        let o = gates.next().unwrap().mapv_into(sigmoid);
+
+        let c_next = f.clone() * c_prev.clone() + g.clone() * i.clone();
+        let tanh_c_next = c_next.clone().mapv_into(|x| x.tanh());
+        let h_next = o.clone() * tanh_c_next.clone();
+
+        self.x = Some(x);
+        self.h_prev = Some(h_prev);
+        self.c_prev = Some(c_prev);
+        self.wx = Some(wx);
+        self.wh = Some(wh);
+        self.gates = Some(vec![f, g, i, o]);
+        self.tanh_c_next = Some(tanh_c_next);
+        Self::Output { h_next, c_next }
+    }
+
+    fn backward(&self, dout: Self::Output) -> Self::DInput {
+        assert!(self.x.is_some());
+        assert!(self.gates.is_some());
+        let x = self.x.as_ref().unwrap();
+        let h_prev = self.h_prev.as_ref().unwrap();
+        let c_prev = self.c_prev.as_ref().unwrap();
+        let wx = self.wx.as_ref().unwrap();
+        let wh = self.wh.as_ref().unwrap();
+        let tanh_c_next = self.tanh_c_next.as_ref().unwrap();
+        let (f, g, i, o) = match self.gates.as_deref().unwrap() {
+            [f, g, i, o] => (f, g, i, o),
+            _ => unreachable!(),
+        };
+        let Self::Output {
+            h_next: dh_next,
+            c_next: dc_next,
+        } = dout;
+
+        let ds =
+            dc_next + dh_next.clone() * o.clone() * tanh_c_next.clone().mapv_into(|t| 1. - t * t);
+        let dc_prev = ds.clone() * f.clone();
+
+        let sigmoid_grad = |y: &M2| y.clone() * y.clone().mapv_into(|y| 1. - y);
+        let df = ds.clone() * c_prev.clone() * sigmoid_grad(f);
+        let dg = ds.clone() * i.clone() * g.clone().mapv_into(|g| 1. - g * g);
+        let di = ds * g.clone() * sigmoid_grad(i);
+        let d_o = dh_next * tanh_c_next.clone() * sigmoid_grad(o);
+        let da = M2::concat_columns(&[df, dg, di, d_o]);
+
+        Self::DInput {
+            dx: da.dot(&wx.t()),
+            dh_prev: da.dot(&wh.t()),
+            dc_prev,
+            dwx: x.t().dot(&da),
+            dwh: h_prev.t().dot(&da),
+            db: da.sum_axis_zero(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use ndarray::{array, Array1, Array2};
+
+    use crate::layers::gradient_check::{assert_gradient_eq, numerical_gradient};
+
+    use super::*;
+
+    fn input() -> InputOfLstmLayer<Array2<f32>, Array1<f32>> {
+        InputOfLstmLayer {
+            x: array![[0.1, -0.2, 0.3], [0.4, 0.5, -0.6]],
+            h_prev: array![[0.2, -0.1], [-0.3, 0.4]],
+            c_prev: array![[0.5, 0.3], [-0.2, -0.6]],
+            wx: array![
+                [0.5, -0.4, 0.2, 0.1, -0.3, 0.6, 0.4, -0.2],
+                [0.3, 0.2, -0.5, 0.4, 0.1, -0.1, 0.2, 0.3],
+                [-0.1, 0.6, 0.3, -0.2, 0.5, 0.2, -0.4, 0.1]
+            ],
+            wh: array![
+                [0.7, -0.2, 0.1, 0.3, -0.4, 0.2, 0.5, -0.1],
+                [0.1, 0.3, -0.2, 0.6, 0.2, -0.5, 0.1, 0.4]
+            ],
+            b: array![0.05, -0.05, 0.1, 0., -0.1, 0.2, 0.05, -0.15],
+        }
+    }
+
+    #[test]
+    fn test_lstm_forward() {
+        let mut lstm = Lstm::new();
+        let output = lstm.forward(input());
+
+        // 第１行目の１番目の隠れ状態を直接計算する
+        let InputOfLstmLayer {
+            x,
+            h_prev,
+            c_prev,
+            wx,
+            wh,
+            b,
+        } = input();
+        let a = x.dot(&wx) + h_prev.dot(&wh) + b;
+        let (f, g, i, o) = (
+            sigmoid(a[[0, 0]]),
+            a[[0, 2]].tanh(),
+            sigmoid(a[[0, 4]]),
+            sigmoid(a[[0, 6]]),
+        );
+        let c_next = f * c_prev[[0, 0]] + g * i;
+        assert_abs_diff_eq!(output.c_next[[0, 0]], c_next, epsilon = 1e-6);
+        assert_abs_diff_eq!(output.h_next[[0, 0]], o * c_next.tanh(), epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_lstm_backward() {
+        // L = Σ dh * H_next + Σ dc * C_next として、各入力に関する微分を数値計算と比較する
+        let dh = array![[1., -2.], [0.5, 3.]];
+        let dc = array![[-0.5, 1.], [2., -1.]];
+        let loss = |input: InputOfLstmLayer<Array2<f32>, Array1<f32>>| {
+            let (h_next, c_next) = Lstm::new().forward(input).into_value();
+            (h_next * &dh).sum() + (c_next * &dc).sum()
+        };
+
+        let mut lstm = Lstm::new();
+        let _ = lstm.forward(input());
+        let dinput = lstm.backward(OutputOfLstmLayer::from((dh.clone(), dc.clone())));
+
+        let base = input();
+        let expected = numerical_gradient(&base.x, |x| loss(InputOfLstmLayer { x, ..input() }));
+        assert_gradient_eq(&dinput.dx, &expected);
+        let expected = numerical_gradient(&base.h_prev, |h_prev| {
+            loss(InputOfLstmLayer { h_prev, ..input() })
+        });
+        assert_gradient_eq(&dinput.dh_prev, &expected);
+        let expected = numerical_gradient(&base.c_prev, |c_prev| {
+            loss(InputOfLstmLayer { c_prev, ..input() })
+        });
+        assert_gradient_eq(&dinput.dc_prev, &expected);
+        let expected = numerical_gradient(&base.wx, |wx| loss(InputOfLstmLayer { wx, ..input() }));
+        assert_gradient_eq(&dinput.dwx, &expected);
+        let expected = numerical_gradient(&base.wh, |wh| loss(InputOfLstmLayer { wh, ..input() }));
+        assert_gradient_eq(&dinput.dwh, &expected);
+        let expected = numerical_gradient(&base.b, |b| loss(InputOfLstmLayer { b, ..input() }));
+        assert_gradient_eq(&dinput.db, &expected);
+    }
+}
@@ -1,5 +1,8 @@
 pub(crate) mod layer;
 
+#[cfg(test)]
+pub(crate) mod gradient_check;
+
 pub(crate) mod add;
 pub(crate) mod branch;
 
@@ -12,5 +15,10 @@ pub(crate) mod sigmoid;
 pub(crate) mod relu;
 
 pub(crate) mod affine;
+pub(crate) mod embedding;
+
+pub(crate) mod gru;
+pub(crate) mod lstm;
+pub(crate) mod rnn;
 
 pub(crate) mod softmax_cross_entropy;
\ No newline at end of file
@@ -0,0 +1,180 @@
+/*
+    RNN の１時刻分の計算
+    H_next = tanh(H_prev.dot(Wh) + X.dot(Wx) + B)
+
+    T = ∂L/∂H_next * (1 - H_next^2)  (tanh の微分)
+    ∂L/∂H_prev = T.dot(Wh^T)
+    ∂L/∂X = T.dot(Wx^T)
+    ∂L/∂Wh = H_prev^T.dot(T)
+    ∂L/∂Wx = X^T.dot(T)
+    ∂L/∂B = Σ_n T[n]  (バッチ方向の和)
+*/
+
+use std::marker::PhantomData;
+
+use crate::matrix::{matrix_one_dim::MatrixOneDim, matrix_two_dim::MatrixTwoDim};
+
+use super::layer::Layer;
+
+pub(crate) struct Rnn<M2, M1> {
+    x: Option<M2>,
+    h_prev: Option<M2>,
+    h_next: Option<M2>,
+    wx: Option<M2>,
+    wh: Option<M2>,
+    ph: PhantomData<M1>,
+}
+
+pub(crate) struct InputOfRnnLayer<M2, M1> {
+    pub(crate) x: M2,
+    pub(crate) h_prev: M2,
+    pub(crate) wx: M2,
+    pub(crate) wh: M2,
+    pub(crate) b: M1,
+}
+
+pub(crate) struct DInputOfRnnLayer<M2, M1> {
+    pub(crate) dx: M2,
+    pub(crate) dh_prev: M2,
+    pub(crate) dwx: M2,
+    pub(crate) dwh: M2,
+    pub(crate) db: M1,
+}
+
+pub(crate) struct OutputOfRnnLayer<M2> {
+    h_next: M2,
+}
+
+impl<M2> OutputOfRnnLayer<M2> {
+    pub fn into_value(self) -> M2 {
+        self.h_next
+    }
+}
+
+impl<M2> From<M2> for OutputOfRnnLayer<M2> {
+    fn from(value: M2) -> Self {
+        Self { h_next: value }
+    }
+}
+
+impl<M2, M1> Layer<M2, M1> for Rnn<M2, M1>
+where
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    type Input = InputOfRnnLayer<M2, M1>;
+    type Output = OutputOfRnnLayer<M2>;
+    type DInput = DInputOfRnnLayer<M2, M1>;
+
+    fn new() -> Self {
+        Self {
+            x: None,
+            h_prev: None,
+            h_next: None,
+            wx: None,
+            wh: None,
+            ph: PhantomData,
+        }
+    }
+
+    fn forward(&mut self, input: Self::Input) -> Self::Output {
+        let Self::Input {
+            x,
+            h_prev,
+            wx,
+            wh,
+            b,
+        } = input;
+        let h_next = (h_prev.dot(&wh) + x.dot(&wx) + b).mapv_into(|x| x.tanh());
+
+        self.x = Some(x);
+        self.h_prev = Some(h_prev);
+        self.h_next = Some(h_next.clone());
+        self.wx = Some(wx);
+        self.wh = Some(wh);
+        Self::Output { h_next }
+    }
+
+    fn backward(&self, dout: Self::Output) -> Self::DInput {
+        assert!(self.x.is_some());
+        assert!(self.h_prev.is_some());
+        assert!(self.h_next.is_some());
+        let x = self.x.as_ref().unwrap();
+        let h_prev = self.h_prev.as_ref().unwrap();
+        let h_next = self.h_next.as_ref().unwrap();
+        let wx = self.wx.as_ref().unwrap();
+        let wh = self.wh.as_ref().unwrap();
+        let Self::Output { h_next: dh_next } = dout;
+
+        let dt = dh_next * h_next.clone().mapv_into(|h| 1. - h * h);
+        Self::DInput {
+            dx: dt.dot(&wx.t()),
+            dh_prev: dt.dot(&wh.t()),
+            dwx: x.t().dot(&dt),
+            dwh: h_prev.t().dot(&dt),
+            db: dt.sum_axis_zero(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Array1, Array2};
+
+    use crate::layers::gradient_check::{assert_gradient_eq, numerical_gradient};
+
+    use super::*;
+
+    fn input() -> InputOfRnnLayer<Array2<f32>, Array1<f32>> {
+        InputOfRnnLayer {
+            x: array![[0.1, -0.2, 0.3], [0.4, 0.5, -0.6]],
+            h_prev: array![[0.2, -0.1], [-0.3, 0.4]],
+            wx: array![[0.5, -0.4], [0.3, 0.2], [-0.1, 0.6]],
+            wh: array![[0.7, -0.2], [0.1, 0.3]],
+            b: array![0.05, -0.05],
+        }
+    }
+
+    #[test]
+    fn test_rnn_forward() {
+        let mut rnn = Rnn::new();
+        let output = rnn.forward(input());
+
+        let InputOfRnnLayer {
+            x,
+            h_prev,
+            wx,
+            wh,
+            b,
+        } = input();
+        let expected = (h_prev.dot(&wh) + x.dot(&wx) + b).mapv(f32::tanh);
+        assert_eq!(output.h_next, expected);
+    }
+
+    #[test]
+    fn test_rnn_backward() {
+        // L = Σ dout * H_next として、各入力に関する微分を数値計算と比較する
+        let dout = array![[1., -2.], [0.5, 3.]];
+        let loss = |input: InputOfRnnLayer<Array2<f32>, Array1<f32>>| {
+            (Rnn::new().forward(input).h_next * &dout).sum()
+        };
+
+        let mut rnn = Rnn::new();
+        let _ = rnn.forward(input());
+        let dinput = rnn.backward(OutputOfRnnLayer::from(dout.clone()));
+
+        let base = input();
+        let expected = numerical_gradient(&base.x, |x| loss(InputOfRnnLayer { x, ..input() }));
+        assert_gradient_eq(&dinput.dx, &expected);
+        let expected = numerical_gradient(&base.h_prev, |h_prev| {
+            loss(InputOfRnnLayer { h_prev, ..input() })
+        });
+        assert_gradient_eq(&dinput.dh_prev, &expected);
+        let expected = numerical_gradient(&base.wx, |wx| loss(InputOfRnnLayer { wx, ..input() }));
+        assert_gradient_eq(&dinput.dwx, &expected);
+        let expected = numerical_gradient(&base.wh, |wh| loss(InputOfRnnLayer { wh, ..input() }));
+        assert_gradient_eq(&dinput.dwh, &expected);
+        let expected = numerical_gradient(&base.b, |b| loss(InputOfRnnLayer { b, ..input() }));
+        assert_gradient_eq(&dinput.db, &expected);
+    }
+}
@@ -9,6 +9,10 @@ use crate::matrix::{matrix_one_dim::MatrixOneDim, matrix_two_dim::MatrixTwoDim};
 
 use super::layer::Layer;
 
+pub(crate) fn sigmoid(x: f32) -> f32 {
+    1. / (1. + (-x).exp())
+}
+
 pub(crate) struct Sigmoid<M2, M1> {
     out: Option<M2>,
     ph: PhantomData<M1>,
@@ -73,7 +77,7 @@ where
 
     fn forward(&mut self, input: Self::Input) -> Self::Output {
         let Self::Input { input } = input;
-        let out = input.mapv_into(|x| 1. / (1. + (-x).exp()));
+        let out = input.mapv_into(sigmoid);
         self.out = Some(out.clone());
         Self::Output { out }
     }
@@ -85,7 +85,7 @@ where
         exp / sum
     }
 
-    fn softmax(input: M2) -> M2 {
+    pub(crate) fn softmax(input: M2) -> M2 {
         input.mapv_into_for_each_rows(Self::softmax_1d)
     }
 
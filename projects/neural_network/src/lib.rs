@@ -3,4 +3,5 @@ pub(crate) mod layers;
 pub mod matrix;
 pub mod network;
 pub mod optimizer;
+pub mod rnn_lm_trainer;
 pub mod trainer;
@@ -0,0 +1,210 @@
+use std::ops::Range;
+
+use ndarray::{stack, Array1, Array2, Array3, Axis};
+
+use super::{matrix_one_dim::MatrixOneDim, matrix_two_dim::MatrixTwoDim, tensor::Tensor};
+
+// 時系列データ (バッチサイズ, 時刻, 次元) を表す３次元配列
+// 形の操作は Tensor の軸を指定する操作を３次元・時刻の軸に限って使う
+pub trait MatrixThreeDim<M2, M1>: Tensor
+where
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    fn dim(&self) -> (usize, usize, usize) {
+        let dims = self.dims();
+        (dims[0], dims[1], dims[2])
+    }
+    fn zeros(dim: (usize, usize, usize)) -> Self {
+        Self::zeros_of_shape(&[dim.0, dim.1, dim.2])
+    }
+    fn sum(&self) -> f32;
+    // 各時刻の (バッチサイズ, 次元) の行列を時刻の方向に並べる
+    fn from_time_slices(slices: Vec<M2>) -> Self;
+    fn into_time_slices(self) -> Vec<M2>;
+    // (バッチサイズ * 時刻, 次元) の行列との相互変換
+    fn into_matrix(self) -> M2;
+    fn from_matrix(matrix: M2, batch_size: usize, time_size: usize) -> Self;
+    // 要素の並びを保ったまま形を変える
+    fn reshape(self, dim: (usize, usize, usize)) -> Self {
+        self.reshape_into(&[dim.0, dim.1, dim.2])
+    }
+    // 新しい配列の i 番目の軸を元の配列の axes.i 番目の軸にする
+    fn permute_axes(self, axes: (usize, usize, usize)) -> Self {
+        self.permute(&[axes.0, axes.1, axes.2])
+    }
+    // 時刻 range の部分を取り出す (Truncated BPTT のブロック分割など)
+    fn slice_time(&self, range: Range<usize>) -> Self {
+        self.slice_along(1, range)
+    }
+    fn concat_time(blocks: &[Self]) -> Self {
+        Self::concat_along(1, blocks)
+    }
+    // (バッチサイズ, 次元) の行列を全時刻に複製する
+    fn broadcast_time(matrix: &M2, time_size: usize) -> Self;
+    // broadcast_time の逆伝播にあたる時刻方向の和
+    fn sum_time(&self) -> M2;
+}
+
+impl MatrixThreeDim<Array2<f32>, Array1<f32>> for Array3<f32> {
+    fn sum(&self) -> f32 {
+        self.sum()
+    }
+
+    fn from_time_slices(slices: Vec<Array2<f32>>) -> Self {
+        // ソースに１つ以上の時刻が含まれることを要請
+        assert!(!slices.is_empty());
+        let views = slices.iter().map(|slice| slice.view()).collect::<Vec<_>>();
+        stack(Axis(1), &views).unwrap()
+    }
+
+    fn into_time_slices(self) -> Vec<Array2<f32>> {
+        self.axis_iter(Axis(1))
+            .map(|slice| slice.to_owned())
+            .collect()
+    }
+
+    fn into_matrix(self) -> Array2<f32> {
+        let (batch_size, time_size, width) = self.dim();
+        self.as_standard_layout()
+            .into_owned()
+            .into_shape((batch_size * time_size, width))
+            .unwrap()
+    }
+
+    fn from_matrix(matrix: Array2<f32>, batch_size: usize, time_size: usize) -> Self {
+        let (height, width) = matrix.dim();
+        assert_eq!(height, batch_size * time_size);
+        matrix
+            .as_standard_layout()
+            .into_owned()
+            .into_shape((batch_size, time_size, width))
+            .unwrap()
+    }
+
+    fn broadcast_time(matrix: &Array2<f32>, time_size: usize) -> Self {
+        let (batch_size, width) = matrix.dim();
+        matrix
+            .view()
+            .insert_axis(Axis(1))
+            .broadcast((batch_size, time_size, width))
+            .unwrap()
+            .to_owned()
+    }
+
+    fn sum_time(&self) -> Array2<f32> {
+        self.sum_axis(Axis(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn test_time_slices() {
+        let x = array![
+            [[1., 2.], [3., 4.], [5., 6.]],
+            [[7., 8.], [9., 10.], [11., 12.]]
+        ];
+        let slices = x.clone().into_time_slices();
+        assert_eq!(
+            slices,
+            vec![
+                array![[1., 2.], [7., 8.]],
+                array![[3., 4.], [9., 10.]],
+                array![[5., 6.], [11., 12.]]
+            ]
+        );
+        assert_eq!(Array3::from_time_slices(slices), x);
+    }
+
+    #[test]
+    fn test_matrix() {
+        let x = array![
+            [[1., 2.], [3., 4.], [5., 6.]],
+            [[7., 8.], [9., 10.], [11., 12.]]
+        ];
+        let matrix = x.clone().into_matrix();
+        assert_eq!(
+            matrix,
+            array![
+                [1., 2.],
+                [3., 4.],
+                [5., 6.],
+                [7., 8.],
+                [9., 10.],
+                [11., 12.]
+            ]
+        );
+        assert_eq!(Array3::from_matrix(matrix, 2, 3), x);
+    }
+
+    #[test]
+    fn test_reshape_and_permute_axes() {
+        let x = array![
+            [[1., 2.], [3., 4.], [5., 6.]],
+            [[7., 8.], [9., 10.], [11., 12.]]
+        ];
+        assert_eq!(
+            x.clone().reshape((3, 2, 2)),
+            array![
+                [[1., 2.], [3., 4.]],
+                [[5., 6.], [7., 8.]],
+                [[9., 10.], [11., 12.]]
+            ]
+        );
+
+        // (バッチサイズ, 時刻, 次元) -> (時刻, バッチサイズ, 次元)
+        let permuted = x.permute_axes((1, 0, 2));
+        assert_eq!(
+            permuted,
+            array![
+                [[1., 2.], [7., 8.]],
+                [[3., 4.], [9., 10.]],
+                [[5., 6.], [11., 12.]]
+            ]
+        );
+        // 軸を入れ替えた後の reshape は入れ替え後の並びに従う
+        assert_eq!(
+            permuted.reshape((1, 6, 2)).into_matrix(),
+            array![
+                [1., 2.],
+                [7., 8.],
+                [3., 4.],
+                [9., 10.],
+                [5., 6.],
+                [11., 12.]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_slice_and_concat_time() {
+        let x = array![
+            [[1., 2.], [3., 4.], [5., 6.]],
+            [[7., 8.], [9., 10.], [11., 12.]]
+        ];
+        let former = x.slice_time(0..2);
+        let latter = x.slice_time(2..3);
+        assert_eq!(former, array![[[1., 2.], [3., 4.]], [[7., 8.], [9., 10.]]]);
+        assert_eq!(latter, array![[[5., 6.]], [[11., 12.]]]);
+        assert_eq!(Array3::concat_time(&[former, latter]), x);
+    }
+
+    #[test]
+    fn test_broadcast_time() {
+        let h = array![[1., 2.], [3., 4.]];
+        let x = Array3::broadcast_time(&h, 3);
+        assert_eq!(
+            x,
+            array![
+                [[1., 2.], [1., 2.], [1., 2.]],
+                [[3., 4.], [3., 4.], [3., 4.]]
+            ]
+        );
+        assert_eq!(x.sum_time(), h * 3.);
+    }
+}
@@ -1,6 +1,6 @@
 use std::ops::{Add, Div, Mul, Sub};
 
-use ndarray::{Array1, Array2, Axis};
+use ndarray::{concatenate, s, Array1, Array2, Axis};
 use ndarray_rand::{rand_distr::Normal, RandomExt};
 
 use super::matrix_one_dim::MatrixOneDim;
@@ -27,6 +27,7 @@ where
         F: FnMut(f32) -> f32;
     fn ones_like(&self) -> Self;
     fn zeros_like(&self) -> Self;
+    fn zeros(dim: (usize, usize)) -> Self;
     fn dim(&self) -> (usize, usize);
     fn mapv_into_for_each_rows<F>(self, f: F) -> Self
     where
@@ -37,6 +38,19 @@ where
     where
         F: FnMut(&f32, &f32) -> f32;
     fn random_normal(dim: (usize, usize), mean: f32, std_dev: f32) -> Self;
+    // 列方向に n 等分する
+    fn split_columns(&self, n: usize) -> Vec<Self>;
+    // 列方向に連結する
+    fn concat_columns(matrices: &[Self]) -> Self;
+    // indices で指定した行を並べる
+    fn select_rows(&self, indices: &[usize]) -> Self;
+    // self の indices[k] 行目に rows の k 行目を加える
+    fn add_to_rows(&mut self, indices: &[usize], rows: &Self);
+    fn indexed_mapv_into<F>(self, f: F) -> Self
+    where
+        F: FnMut((usize, usize), f32) -> f32;
+    // 行ごとに順に並べた要素
+    fn to_vec(&self) -> Vec<f32>;
 }
 
 impl MatrixTwoDim<Array1<f32>> for Array2<f32> {
@@ -67,6 +81,10 @@ impl MatrixTwoDim<Array1<f32>> for Array2<f32> {
         Array2::zeros(self.dim())
     }
 
+    fn zeros(dim: (usize, usize)) -> Self {
+        Array2::zeros(dim)
+    }
+
     fn dim(&self) -> (usize, usize) {
         self.dim()
     }
@@ -116,4 +134,43 @@ impl MatrixTwoDim<Array1<f32>> for Array2<f32> {
     fn random_normal(dim: (usize, usize), mean: f32, std_dev: f32) -> Self {
         Array2::random(dim, Normal::new(mean, std_dev).unwrap())
     }
+
+    fn split_columns(&self, n: usize) -> Vec<Self> {
+        let (_, width) = self.dim();
+        assert_eq!(width % n, 0);
+        let chunk = width / n;
+        (0..n)
+            .map(|i| self.slice(s![.., i * chunk..(i + 1) * chunk]).to_owned())
+            .collect()
+    }
+
+    fn concat_columns(matrices: &[Self]) -> Self {
+        let views = matrices.iter().map(|m| m.view()).collect::<Vec<_>>();
+        concatenate(Axis(1), &views).unwrap()
+    }
+
+    fn select_rows(&self, indices: &[usize]) -> Self {
+        self.select(Axis(0), indices)
+    }
+
+    fn add_to_rows(&mut self, indices: &[usize], rows: &Self) {
+        assert_eq!(indices.len(), rows.dim().0);
+        for (&index, row) in indices.iter().zip(rows.rows()) {
+            let mut target = self.row_mut(index);
+            target += &row;
+        }
+    }
+
+    fn indexed_mapv_into<F>(mut self, mut f: F) -> Self
+    where
+        F: FnMut((usize, usize), f32) -> f32,
+    {
+        self.indexed_iter_mut()
+            .for_each(|(index, value)| *value = (f)(index, *value));
+        self
+    }
+
+    fn to_vec(&self) -> Vec<f32> {
+        self.iter().copied().collect()
+    }
 }
@@ -1,2 +1,4 @@
 pub mod matrix_one_dim;
-pub mod matrix_two_dim;
\ No newline at end of file
+pub mod matrix_three_dim;
+pub mod matrix_two_dim;
+pub mod tensor;
@@ -0,0 +1,212 @@
+use std::ops::{Add, Div, Mul, Range, Sub};
+
+use ndarray::{concatenate, Array, Axis, Dimension, IxDyn, RemoveAxis, Slice};
+
+// 任意の次元の配列 (時系列の (バッチサイズ, 時刻, 次元)、画像の (バッチサイズ, チャンネル, 高さ, 幅) など)
+// ndarray の同名のメソッドと区別できるよう、メソッド名は ndarray と重ならないものにしている
+pub trait Tensor:
+    Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Mul<f32, Output = Self>
+    + Div<f32, Output = Self>
+    + Clone
+{
+    fn dims(&self) -> Vec<usize>;
+    fn rank(&self) -> usize {
+        self.dims().len()
+    }
+    fn zeros_of_shape(shape: &[usize]) -> Self;
+    // 要素を各軸の後ろから順に進める順に並べたもの
+    fn from_flat_vec(shape: &[usize], values: Vec<f32>) -> Self;
+    fn mapv_into<F>(self, f: F) -> Self
+    where
+        F: FnMut(f32) -> f32;
+    // 要素の並びを保ったまま形を変える（軸の数は変えない）
+    fn reshape_into(self, shape: &[usize]) -> Self;
+    // 新しい配列の i 番目の軸を元の配列の axes[i] 番目の軸にする
+    fn permute(self, axes: &[usize]) -> Self;
+    // axis 番目の軸について range の範囲を取り出す
+    fn slice_along(&self, axis: usize, range: Range<usize>) -> Self;
+    fn concat_along(axis: usize, tensors: &[Self]) -> Self;
+    // 長さ１の軸を繰り返して shape に合わせる
+    fn broadcast_to(&self, shape: &[usize]) -> Self;
+    // axis 番目の軸について和を取る（軸は長さ１で残す）
+    // broadcast_to の逆伝播に使う
+    fn sum_along_keep_dim(&self, axis: usize) -> Self;
+}
+
+// 軸の数が D と異なる shape は受け付けない
+fn to_dim<D: Dimension>(shape: &[usize]) -> D {
+    D::from_dimension(&IxDyn(shape)).expect("the number of axes does not match")
+}
+
+impl<D: RemoveAxis> Tensor for Array<f32, D> {
+    fn dims(&self) -> Vec<usize> {
+        self.shape().to_vec()
+    }
+
+    fn zeros_of_shape(shape: &[usize]) -> Self {
+        Array::zeros(to_dim::<D>(shape))
+    }
+
+    fn from_flat_vec(shape: &[usize], values: Vec<f32>) -> Self {
+        Array::from_shape_vec(to_dim::<D>(shape), values).unwrap()
+    }
+
+    fn mapv_into<F>(self, f: F) -> Self
+    where
+        F: FnMut(f32) -> f32,
+    {
+        self.mapv_into(f)
+    }
+
+    fn reshape_into(self, shape: &[usize]) -> Self {
+        self.as_standard_layout()
+            .into_owned()
+            .into_shape(to_dim::<D>(shape))
+            .unwrap()
+    }
+
+    fn permute(self, axes: &[usize]) -> Self {
+        self.permuted_axes(to_dim::<D>(axes))
+            .as_standard_layout()
+            .into_owned()
+    }
+
+    fn slice_along(&self, axis: usize, range: Range<usize>) -> Self {
+        self.slice_axis(Axis(axis), Slice::from(range)).to_owned()
+    }
+
+    fn concat_along(axis: usize, tensors: &[Self]) -> Self {
+        assert!(!tensors.is_empty());
+        let views = tensors.iter().map(|t| t.view()).collect::<Vec<_>>();
+        concatenate(Axis(axis), &views).unwrap()
+    }
+
+    fn broadcast_to(&self, shape: &[usize]) -> Self {
+        self.broadcast(to_dim::<D>(shape)).unwrap().to_owned()
+    }
+
+    fn sum_along_keep_dim(&self, axis: usize) -> Self {
+        self.sum_axis(Axis(axis))
+            .insert_axis(Axis(axis))
+            .into_dimensionality()
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Array4, ArrayD};
+
+    use super::*;
+
+    // (2, 3, 2) の配列 [[[0, 1], [2, 3], [4, 5]], [[6, 7], [8, 9], [10, 11]]]
+    fn tensor() -> ArrayD<f32> {
+        ArrayD::from_flat_vec(&[2, 3, 2], (0..12).map(|x| x as f32).collect())
+    }
+
+    // (バッチサイズ, チャンネル, 高さ, 幅) = (2, 2, 2, 2) の画像
+    fn images() -> Array4<f32> {
+        Array4::from_flat_vec(&[2, 2, 2, 2], (0..16).map(|x| x as f32).collect())
+    }
+
+    #[test]
+    fn test_reshape_into() {
+        let reshaped = tensor().reshape_into(&[3, 4]);
+        assert_eq!(reshaped.dims(), vec![3, 4]);
+        assert_eq!(
+            reshaped,
+            array![[0., 1., 2., 3.], [4., 5., 6., 7.], [8., 9., 10., 11.]].into_dyn()
+        );
+
+        // 軸を入れ替えた後でも要素の並びは新しい形に沿う
+        let permuted = tensor().permute(&[1, 0, 2]).reshape_into(&[12]);
+        assert_eq!(
+            permuted,
+            array![0., 1., 6., 7., 2., 3., 8., 9., 4., 5., 10., 11.].into_dyn()
+        );
+    }
+
+    #[test]
+    fn test_permute() {
+        let permuted = tensor().permute(&[2, 0, 1]);
+        assert_eq!(permuted.dims(), vec![2, 2, 3]);
+        assert_eq!(
+            permuted,
+            array![[[0., 2., 4.], [6., 8., 10.]], [[1., 3., 5.], [7., 9., 11.]]].into_dyn()
+        );
+
+        // (バッチサイズ, チャンネル, 高さ, 幅) -> (バッチサイズ, 高さ, 幅, チャンネル)
+        let channels_last = images().permute(&[0, 2, 3, 1]);
+        assert_eq!(channels_last.rank(), 4);
+        assert_eq!(channels_last.dims(), vec![2, 2, 2, 2]);
+        // 画素ごとにチャンネルの値が並ぶ
+        assert_eq!(
+            channels_last
+                .reshape_into(&[1, 1, 8, 2])
+                .slice_along(2, 0..2),
+            Array4::from_flat_vec(&[1, 1, 2, 2], vec![0., 4., 1., 5.])
+        );
+    }
+
+    #[test]
+    fn test_slice_along_and_concat_along() {
+        let former = tensor().slice_along(1, 0..1);
+        let latter = tensor().slice_along(1, 1..3);
+        assert_eq!(former, array![[[0., 1.]], [[6., 7.]]].into_dyn());
+        assert_eq!(latter.dims(), vec![2, 2, 2]);
+        assert_eq!(ArrayD::concat_along(1, &[former, latter]), tensor());
+
+        // 画像をチャンネルごとに分けて戻す
+        let channels = (0..2)
+            .map(|c| images().slice_along(1, c..c + 1))
+            .collect::<Vec<_>>();
+        assert_eq!(channels[1].dims(), vec![2, 1, 2, 2]);
+        assert_eq!(Array4::concat_along(1, &channels), images());
+    }
+
+    #[test]
+    fn test_broadcast() {
+        let bias = array![[[10., 20.]]].into_dyn();
+        let broadcasted = bias.broadcast_to(&[2, 3, 2]);
+        assert_eq!(broadcasted.dims(), vec![2, 3, 2]);
+        assert_eq!(
+            tensor() + broadcasted,
+            ArrayD::from_flat_vec(
+                &[2, 3, 2],
+                (0..12)
+                    .map(|x| x as f32 + if x % 2 == 0 { 10. } else { 20. })
+                    .collect()
+            )
+        );
+
+        // broadcast の逆伝播にあたる和
+        assert_eq!(
+            tensor().sum_along_keep_dim(1),
+            array![[[6., 9.]], [[24., 27.]]].into_dyn()
+        );
+
+        // チャンネルごとのバイアス (1, チャンネル, 1, 1)
+        let bias = Array4::from_flat_vec(&[1, 2, 1, 1], vec![100., 200.]);
+        let output = images() + bias.broadcast_to(&[2, 2, 2, 2]);
+        assert_eq!(output[[1, 0, 1, 1]], 111.);
+        assert_eq!(output[[1, 1, 0, 0]], 212.);
+        // バイアスの勾配はチャンネル以外の軸について和を取る
+        assert_eq!(
+            output
+                .sum_along_keep_dim(0)
+                .sum_along_keep_dim(2)
+                .sum_along_keep_dim(3),
+            Array4::from_flat_vec(&[1, 2, 1, 1], vec![844., 1676.])
+        );
+    }
+
+    #[test]
+    fn test_mapv_into() {
+        let doubled = Tensor::mapv_into(images(), |x| 2. * x);
+        assert_eq!(doubled, images() * 2.);
+        assert_eq!(Array4::zeros_of_shape(&[2, 2, 2, 2]), images() * 0.);
+    }
+}
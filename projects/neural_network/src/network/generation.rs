@@ -0,0 +1,232 @@
+/*
+    学習済みの言語モデルによる単語列の生成
+    直前の単語を入力し、出力された次の単語の確率分布から次の単語を選ぶことを繰り返す
+
+    確率分布 p = softmax(s / T)  (s: スコア、T: 温度)
+    T < 1 で分布が鋭く（確率の高い単語が選ばれやすく）なり、T > 1 で平らになる
+
+    Greedy: 最もスコアの高い単語を選ぶ（T -> 0 の極限）
+    Temperature: p からそのままサンプリングする
+    TopK: 確率の高い上位 k 個の単語に絞り、確率を正規化し直してサンプリングする
+    TopP (nucleus): 確率の高い順に累積確率が p 以上になるまでの単語に絞ってサンプリングする
+*/
+
+use rand::{distributions::WeightedIndex, prelude::Distribution, Rng};
+
+use crate::matrix::{
+    matrix_one_dim::MatrixOneDim, matrix_three_dim::MatrixThreeDim, matrix_two_dim::MatrixTwoDim,
+};
+
+use super::language_model::LanguageModel;
+
+#[derive(Clone, Copy, Debug)]
+pub enum Sampling {
+    Greedy,
+    Temperature(f32),
+    TopK { k: usize, temperature: f32 },
+    TopP { p: f32, temperature: f32 },
+}
+
+impl Sampling {
+    // scores: 各単語のスコア (softmax を取る前の値)
+    pub fn sample<R: Rng>(&self, scores: &[f32], rng: &mut R) -> usize {
+        assert!(!scores.is_empty());
+        let temperature = match *self {
+            Sampling::Greedy => return argmax(scores),
+            Sampling::Temperature(temperature)
+            | Sampling::TopK { temperature, .. }
+            | Sampling::TopP { temperature, .. } => temperature,
+        };
+        assert!(temperature > 0.);
+
+        let probs = softmax(scores, temperature);
+        // 確率の高い順に並べた単語
+        let mut ids = (0..scores.len()).collect::<Vec<_>>();
+        ids.sort_by(|&a, &b| probs[b].total_cmp(&probs[a]));
+
+        let number_of_candidates = match *self {
+            Sampling::TopK { k, .. } => k.clamp(1, ids.len()),
+            Sampling::TopP { p, .. } => {
+                let mut cumulative = 0.;
+                ids.iter()
+                    .position(|&id| {
+                        cumulative += probs[id];
+                        cumulative >= p
+                    })
+                    .map_or(ids.len(), |i| i + 1)
+            }
+            _ => ids.len(),
+        };
+
+        let candidates = &ids[..number_of_candidates];
+        let distribution = WeightedIndex::new(candidates.iter().map(|&id| probs[id])).unwrap();
+        candidates[distribution.sample(rng)]
+    }
+}
+
+fn argmax(scores: &[f32]) -> usize {
+    (0..scores.len())
+        .max_by(|&a, &b| scores[a].total_cmp(&scores[b]))
+        .unwrap()
+}
+
+fn softmax(scores: &[f32], temperature: f32) -> Vec<f32> {
+    // オーバーフロー対策として最大値を引く
+    let max = scores[argmax(scores)];
+    let exp = scores
+        .iter()
+        .map(|&s| ((s - max) / temperature).exp())
+        .collect::<Vec<_>>();
+    let sum: f32 = exp.iter().sum();
+    exp.into_iter().map(|e| e / sum).collect()
+}
+
+pub struct InitParamsOfGeneration {
+    // start_id を含めた単語数の上限（１以上）
+    pub max_len: usize,
+    // これらの単語が選ばれたら生成を終える（選ばれた単語は結果に含めない）
+    pub stop_ids: Vec<usize>,
+    pub sampling: Sampling,
+}
+
+// start_id から始まる単語 ID の列を生成する
+// 隠れ状態は生成の前後で捨てる
+pub fn generate<Net, M3, M2, M1, R>(
+    network: &mut Net,
+    start_id: usize,
+    params: InitParamsOfGeneration,
+    rng: &mut R,
+) -> Vec<usize>
+where
+    Net: LanguageModel<M3, M2, M1>,
+    M3: MatrixThreeDim<M2, M1>,
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+    R: Rng,
+{
+    let InitParamsOfGeneration {
+        max_len,
+        stop_ids,
+        sampling,
+    } = params;
+    assert!(max_len >= 1);
+
+    network.reset_state();
+    let mut ids = vec![start_id];
+    while ids.len() < max_len {
+        let last_id = *ids.last().unwrap();
+        // (1, 1, 語彙数) のスコア
+        let scores = network.predict(&[vec![last_id]]).into_matrix().to_vec();
+        let next_id = sampling.sample(&scores, rng);
+        if stop_ids.contains(&next_id) {
+            break;
+        }
+        ids.push(next_id);
+    }
+    network.reset_state();
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::network::test_support::CyclicLm;
+
+    use super::*;
+
+    // 各単語 ID の出現回数
+    fn histogram(sampling: Sampling, scores: &[f32], n: usize) -> Vec<usize> {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut counts = vec![0; scores.len()];
+        for _ in 0..n {
+            counts[sampling.sample(scores, &mut rng)] += 1;
+        }
+        counts
+    }
+
+    // 確率は [0.1, 0.6, 0.3]
+    fn scores() -> Vec<f32> {
+        [0.1_f32, 0.6, 0.3].iter().map(|p| p.ln()).collect()
+    }
+
+    #[test]
+    fn test_greedy() {
+        assert_eq!(histogram(Sampling::Greedy, &scores(), 10), vec![0, 10, 0]);
+    }
+
+    #[test]
+    fn test_temperature() {
+        let n = 10000;
+        let counts = histogram(Sampling::Temperature(1.), &scores(), n);
+        for (count, p) in counts.into_iter().zip([0.1, 0.6, 0.3]) {
+            assert_abs_diff_eq!(count as f32 / n as f32, p, epsilon = 0.02);
+        }
+
+        // 温度を下げると確率の高い単語に偏る: p ∝ [0.01, 0.36, 0.09]
+        let counts = histogram(Sampling::Temperature(0.5), &scores(), n);
+        for (count, p) in counts
+            .into_iter()
+            .zip([0.01 / 0.46, 0.36 / 0.46, 0.09 / 0.46])
+        {
+            assert_abs_diff_eq!(count as f32 / n as f32, p, epsilon = 0.02);
+        }
+    }
+
+    #[test]
+    fn test_top_k() {
+        let n = 10000;
+        let sampling = Sampling::TopK {
+            k: 2,
+            temperature: 1.,
+        };
+        let counts = histogram(sampling, &scores(), n);
+        assert_eq!(counts[0], 0);
+        assert_abs_diff_eq!(counts[1] as f32 / n as f32, 2. / 3., epsilon = 0.02);
+
+        let sampling = Sampling::TopK {
+            k: 1,
+            temperature: 1.,
+        };
+        assert_eq!(histogram(sampling, &scores(), 10), vec![0, 10, 0]);
+    }
+
+    #[test]
+    fn test_top_p() {
+        // 0.6 だけで 0.5 に達する
+        let sampling = Sampling::TopP {
+            p: 0.5,
+            temperature: 1.,
+        };
+        assert_eq!(histogram(sampling, &scores(), 10), vec![0, 10, 0]);
+
+        // 0.6 + 0.3 で 0.8 に達する
+        let n = 10000;
+        let sampling = Sampling::TopP {
+            p: 0.8,
+            temperature: 1.,
+        };
+        let counts = histogram(sampling, &scores(), n);
+        assert_eq!(counts[0], 0);
+        assert_abs_diff_eq!(counts[1] as f32 / n as f32, 2. / 3., epsilon = 0.02);
+    }
+
+    #[test]
+    fn test_generate() {
+        let mut network = CyclicLm { vocab_size: 4 };
+        let mut rng = StdRng::seed_from_u64(0);
+        let params = |max_len, stop_ids| InitParamsOfGeneration {
+            max_len,
+            stop_ids,
+            sampling: Sampling::Greedy,
+        };
+
+        let ids = generate(&mut network, 2, params(6, vec![]), &mut rng);
+        assert_eq!(ids, vec![2, 3, 0, 1, 2, 3]);
+
+        // 停止する単語は含めない
+        let ids = generate(&mut network, 2, params(6, vec![1]), &mut rng);
+        assert_eq!(ids, vec![2, 3, 0]);
+    }
+}
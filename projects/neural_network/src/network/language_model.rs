@@ -0,0 +1,23 @@
+use crate::{
+    matrix::{
+        matrix_one_dim::MatrixOneDim, matrix_three_dim::MatrixThreeDim,
+        matrix_two_dim::MatrixTwoDim,
+    },
+    optimizer::optimizer::Optimizer,
+};
+
+// ids, labels: (バッチサイズ, 時刻) の単語 ID
+pub trait LanguageModel<M3, M2, M1>
+where
+    M3: MatrixThreeDim<M2, M1>,
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    // 各時刻について次の単語のスコア (バッチサイズ, 時刻, 語彙数) を返す
+    fn predict(&mut self, ids: &[Vec<usize>]) -> M3;
+    fn forward(&mut self, ids: &[Vec<usize>], labels: &[Vec<usize>]) -> f32;
+    fn backward(&mut self, dout: f32);
+    // max_grad を指定すると、勾配全体のノルムが max_grad を超えないように縮めてから更新する
+    fn update<T: Optimizer>(&mut self, optimizer: &T, max_grad: Option<f32>);
+    fn reset_state(&mut self);
+}
@@ -1,4 +1,6 @@
-use crate::matrix::{matrix_one_dim::MatrixOneDim, matrix_two_dim::MatrixTwoDim};
+use crate::matrix::{
+    matrix_one_dim::MatrixOneDim, matrix_three_dim::MatrixThreeDim, matrix_two_dim::MatrixTwoDim,
+};
 
 pub(crate) trait LayerBase {
     type Params;
@@ -23,3 +25,27 @@ where
     fn forward(&mut self, input: M2, one_hot_labels: M2) -> f32;
     fn backward(&mut self, dout: f32) -> M2;
 }
+
+// 時系列データ (バッチサイズ, 時刻, 次元) を扱うレイヤ
+pub(crate) trait TimeIntermediateLayer<M3, M2, M1>: LayerBase
+where
+    M3: MatrixThreeDim<M2, M1>,
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    fn forward(&mut self, xs: M3) -> M3;
+    fn backward(&mut self, dout: M3) -> M3;
+    // ミニバッチをまたいで引き継いでいる状態を捨てる
+    fn reset_state(&mut self) {}
+}
+
+// labels は (バッチサイズ, 時刻) の正解の ID
+pub(crate) trait TimeLossLayer<M3, M2, M1>: LayerBase
+where
+    M3: MatrixThreeDim<M2, M1>,
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    fn forward(&mut self, xs: M3, labels: &[Vec<usize>]) -> f32;
+    fn backward(&mut self, dout: f32) -> M3;
+}
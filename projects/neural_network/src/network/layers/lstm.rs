@@ -0,0 +1,118 @@
+/*
+    T 時刻分の LSTM をまとめたレイヤ
+    状態は隠れ状態 h と記憶セル c の組で、時刻ごとの計算は Lstm に任せる
+*/
+
+use crate::{
+    layers::{
+        layer::Layer,
+        lstm::{DInputOfLstmLayer, InputOfLstmLayer, Lstm, OutputOfLstmLayer},
+    },
+    matrix::{matrix_one_dim::MatrixOneDim, matrix_two_dim::MatrixTwoDim},
+};
+
+use super::time_recurrent::{ParamsOfRecurrentLayer, RecurrentCell, TimeRecurrentLayer};
+
+pub(crate) type TimeLstmLayer<M3, M2, M1> = TimeRecurrentLayer<M3, M2, M1, Lstm<M2, M1>>;
+
+impl<M2, M1> RecurrentCell<M2, M1> for Lstm<M2, M1>
+where
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    type State = (M2, M2);
+    // ４つのゲートの重みを列方向に [f, g, i, o] の順に連結する
+    const NUMBER_OF_GATES: usize = 4;
+
+    fn zero_state(batch_size: usize, hidden_size: usize) -> Self::State {
+        (
+            M2::zeros((batch_size, hidden_size)),
+            M2::zeros((batch_size, hidden_size)),
+        )
+    }
+
+    fn hidden((h, _): &Self::State) -> &M2 {
+        h
+    }
+
+    // 記憶セルは出力されないので、隠れ状態の勾配にだけ足す
+    fn add_hidden_grad((dh_next, dc_next): Self::State, dh: M2) -> Self::State {
+        (dh_next + dh, dc_next)
+    }
+
+    fn step_forward(
+        &mut self,
+        x: M2,
+        (h, c): Self::State,
+        params: &ParamsOfRecurrentLayer<M2, M1>,
+    ) -> Self::State {
+        self.forward(InputOfLstmLayer {
+            x,
+            h_prev: h,
+            c_prev: c,
+            wx: params.wx.clone(),
+            wh: params.wh.clone(),
+            b: params.b.clone(),
+        })
+        .into_value()
+    }
+
+    fn step_backward(
+        &self,
+        dstate: Self::State,
+    ) -> (M2, Self::State, ParamsOfRecurrentLayer<M2, M1>) {
+        let DInputOfLstmLayer {
+            dx,
+            dh_prev,
+            dc_prev,
+            dwx,
+            dwh,
+            db,
+        } = self.backward(OutputOfLstmLayer::from(dstate));
+        (
+            dx,
+            (dh_prev, dc_prev),
+            ParamsOfRecurrentLayer {
+                wx: dwx,
+                wh: dwh,
+                b: db,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Array1, Array2};
+
+    use crate::layers::gradient_check::{assert_stateful, assert_time_gradients};
+
+    use super::*;
+
+    fn params() -> ParamsOfRecurrentLayer<Array2<f32>, Array1<f32>> {
+        ParamsOfRecurrentLayer {
+            wx: array![
+                [0.5, -0.4, 0.2, 0.1, -0.3, 0.6, 0.4, -0.2],
+                [0.3, 0.2, -0.5, 0.4, 0.1, -0.1, 0.2, 0.3],
+                [-0.1, 0.6, 0.3, -0.2, 0.5, 0.2, -0.4, 0.1]
+            ],
+            wh: array![
+                [0.7, -0.2, 0.1, 0.3, -0.4, 0.2, 0.5, -0.1],
+                [0.1, 0.3, -0.2, 0.6, 0.2, -0.5, 0.1, 0.4]
+            ],
+            b: array![0.05, -0.05, 0.1, 0., -0.1, 0.2, 0.05, -0.15],
+        }
+    }
+
+    #[test]
+    fn test_time_lstm_stateful() {
+        assert_stateful::<TimeLstmLayer<_, _, _>>(params());
+    }
+
+    #[test]
+    fn test_time_lstm_backward() {
+        assert_time_gradients::<TimeLstmLayer<_, _, _>, _>(params(), |params| {
+            (&mut params.wx, &mut params.wh, &mut params.b)
+        });
+    }
+}
@@ -2,5 +2,13 @@ pub(crate) mod layer;
 
 pub(crate) mod affine;
 pub(crate) mod sigmoid;
+pub(crate) mod gru;
+pub(crate) mod lstm;
 pub(crate) mod relu;
+pub(crate) mod rnn;
 pub(crate) mod softmax_cross_entropy;
+
+pub(crate) mod time_affine;
+pub(crate) mod time_embedding;
+pub(crate) mod time_recurrent;
+pub(crate) mod time_softmax_with_loss;
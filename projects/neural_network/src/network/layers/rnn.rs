@@ -0,0 +1,104 @@
+/*
+    T 時刻分の RNN をまとめたレイヤ
+    状態は隠れ状態 h だけで、時刻ごとの計算は Rnn に任せる
+*/
+
+use crate::{
+    layers::{
+        layer::Layer,
+        rnn::{DInputOfRnnLayer, InputOfRnnLayer, OutputOfRnnLayer, Rnn},
+    },
+    matrix::{matrix_one_dim::MatrixOneDim, matrix_two_dim::MatrixTwoDim},
+};
+
+use super::time_recurrent::{ParamsOfRecurrentLayer, RecurrentCell, TimeRecurrentLayer};
+
+pub(crate) type TimeRnnLayer<M3, M2, M1> = TimeRecurrentLayer<M3, M2, M1, Rnn<M2, M1>>;
+
+impl<M2, M1> RecurrentCell<M2, M1> for Rnn<M2, M1>
+where
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    type State = M2;
+    const NUMBER_OF_GATES: usize = 1;
+
+    fn zero_state(batch_size: usize, hidden_size: usize) -> Self::State {
+        M2::zeros((batch_size, hidden_size))
+    }
+
+    fn hidden(h: &Self::State) -> &M2 {
+        h
+    }
+
+    fn add_hidden_grad(dh_next: Self::State, dh: M2) -> Self::State {
+        dh_next + dh
+    }
+
+    fn step_forward(
+        &mut self,
+        x: M2,
+        h: Self::State,
+        params: &ParamsOfRecurrentLayer<M2, M1>,
+    ) -> Self::State {
+        self.forward(InputOfRnnLayer {
+            x,
+            h_prev: h,
+            wx: params.wx.clone(),
+            wh: params.wh.clone(),
+            b: params.b.clone(),
+        })
+        .into_value()
+    }
+
+    fn step_backward(
+        &self,
+        dh_next: Self::State,
+    ) -> (M2, Self::State, ParamsOfRecurrentLayer<M2, M1>) {
+        let DInputOfRnnLayer {
+            dx,
+            dh_prev,
+            dwx,
+            dwh,
+            db,
+        } = self.backward(OutputOfRnnLayer::from(dh_next));
+        (
+            dx,
+            dh_prev,
+            ParamsOfRecurrentLayer {
+                wx: dwx,
+                wh: dwh,
+                b: db,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Array1, Array2};
+
+    use crate::layers::gradient_check::{assert_stateful, assert_time_gradients};
+
+    use super::*;
+
+    fn params() -> ParamsOfRecurrentLayer<Array2<f32>, Array1<f32>> {
+        ParamsOfRecurrentLayer {
+            wx: array![[0.5, -0.4], [0.3, 0.2], [-0.1, 0.6]],
+            wh: array![[0.7, -0.2], [0.1, 0.3]],
+            b: array![0.05, -0.05],
+        }
+    }
+
+    #[test]
+    fn test_time_rnn_stateful() {
+        assert_stateful::<TimeRnnLayer<_, _, _>>(params());
+    }
+
+    #[test]
+    fn test_time_rnn_backward() {
+        assert_time_gradients::<TimeRnnLayer<_, _, _>, _>(params(), |params| {
+            (&mut params.wx, &mut params.wh, &mut params.b)
+        });
+    }
+}
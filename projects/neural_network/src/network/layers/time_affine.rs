@@ -0,0 +1,158 @@
+/*
+    全時刻に同じ重みの Affine を適用するレイヤ
+    (バッチサイズ, 時刻, 次元) を (バッチサイズ * 時刻, 次元) の行列に変形してまとめて計算する
+*/
+
+use std::marker::PhantomData;
+
+use crate::{
+    layers::{
+        affine::{Affine, DInputOfAffineLayer, InputOfAffineLayer, OutputOfAffineLayer},
+        layer::Layer,
+    },
+    matrix::{
+        matrix_one_dim::MatrixOneDim, matrix_three_dim::MatrixThreeDim,
+        matrix_two_dim::MatrixTwoDim,
+    },
+};
+
+use super::{
+    affine::ParamsOfAffineLayer,
+    layer::{LayerBase, TimeIntermediateLayer},
+};
+
+pub(crate) struct TimeAffineLayer<M3, M2, M1> {
+    affine: Affine<M2, M1>,
+    params: ParamsOfAffineLayer<M2, M1>,
+    grads: ParamsOfAffineLayer<M2, M1>,
+    // (バッチサイズ, 時刻)
+    dim: Option<(usize, usize)>,
+    ph: PhantomData<M3>,
+}
+
+impl<M3, M2, M1> LayerBase for TimeAffineLayer<M3, M2, M1>
+where
+    M3: MatrixThreeDim<M2, M1>,
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    type Params = ParamsOfAffineLayer<M2, M1>;
+
+    fn new(params: Self::Params) -> Self {
+        let affine = Affine::new();
+        let grads = ParamsOfAffineLayer {
+            w: M2::zeros_like(&params.w),
+            b: M1::zeros(params.b.len()),
+        };
+        Self {
+            affine,
+            params,
+            grads,
+            dim: None,
+            ph: PhantomData,
+        }
+    }
+
+    fn params_and_grads(&mut self) -> (&mut Self::Params, &Self::Params) {
+        (&mut self.params, &self.grads)
+    }
+}
+
+impl<M3, M2, M1> TimeIntermediateLayer<M3, M2, M1> for TimeAffineLayer<M3, M2, M1>
+where
+    M3: MatrixThreeDim<M2, M1>,
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    fn forward(&mut self, xs: M3) -> M3 {
+        let (batch_size, time_size, _) = xs.dim();
+        self.dim = Some((batch_size, time_size));
+
+        let out = self
+            .affine
+            .forward(InputOfAffineLayer {
+                x: xs.into_matrix(),
+                a: self.params.w.clone(),
+                b: self.params.b.clone(),
+            })
+            .into_value();
+        M3::from_matrix(out, batch_size, time_size)
+    }
+
+    fn backward(&mut self, dout: M3) -> M3 {
+        assert!(self.dim.is_some());
+        let (batch_size, time_size) = self.dim.unwrap();
+
+        let DInputOfAffineLayer { dx, da, db } = self
+            .affine
+            .backward(OutputOfAffineLayer::from(dout.into_matrix()));
+        self.grads.w = da;
+        self.grads.b = db;
+        M3::from_matrix(dx, batch_size, time_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Array1, Array2, Array3};
+
+    use crate::layers::gradient_check::{assert_gradient_eq, numerical_gradient};
+
+    use super::*;
+
+    fn params() -> ParamsOfAffineLayer<Array2<f32>, Array1<f32>> {
+        ParamsOfAffineLayer {
+            w: array![[1., -2.], [0.5, 3.], [-1., 0.]],
+            b: array![0.1, -0.2],
+        }
+    }
+
+    fn xs() -> Array3<f32> {
+        array![
+            [[1., 2., 3.], [4., 5., 6.]],
+            [[-1., 0., 1.], [2., -3., 0.5]]
+        ]
+    }
+
+    #[test]
+    fn test_time_affine_forward() {
+        // 各時刻に Affine を適用したものと一致する
+        let mut layer = TimeAffineLayer::new(params());
+        let out: Array3<f32> = layer.forward(xs());
+
+        let ParamsOfAffineLayer { w, b } = params();
+        let expected = xs()
+            .into_time_slices()
+            .into_iter()
+            .map(|x| x.dot(&w) + &b)
+            .collect();
+        assert_eq!(out, Array3::from_time_slices(expected));
+    }
+
+    #[test]
+    fn test_time_affine_backward() {
+        // L = Σ dout * Y として、入力とパラメータに関する微分を数値計算と比較する
+        let dout = array![[[1., -1.], [0.5, 2.]], [[-2., 0.], [1., 1.]]];
+        let loss = |params: ParamsOfAffineLayer<Array2<f32>, Array1<f32>>, xs: Array3<f32>| {
+            let mut layer = TimeAffineLayer::new(params);
+            let out: Array3<f32> = layer.forward(xs);
+            (out * &dout).sum()
+        };
+
+        let mut layer = TimeAffineLayer::new(params());
+        let _: Array3<f32> = layer.forward(xs());
+        let dxs: Array3<f32> = layer.backward(dout.clone());
+        let (_, grads) = layer.params_and_grads();
+
+        let expected = numerical_gradient(&xs(), |xs| loss(params(), xs));
+        assert_gradient_eq(&dxs, &expected);
+        let expected = numerical_gradient(&params().w, |w| {
+            loss(ParamsOfAffineLayer { w, ..params() }, xs())
+        });
+        assert_gradient_eq(&grads.w, &expected);
+        let expected = numerical_gradient(&params().b, |b| {
+            loss(ParamsOfAffineLayer { b, ..params() }, xs())
+        });
+        assert_gradient_eq(&grads.b, &expected);
+    }
+}
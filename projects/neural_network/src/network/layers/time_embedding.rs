@@ -0,0 +1,145 @@
+/*
+    (バッチサイズ, 時刻) の単語 ID を (バッチサイズ, 時刻, 次元) の単語ベクトルに変換するレイヤ
+*/
+
+use std::{
+    marker::PhantomData,
+    ops::{Add, Mul},
+};
+
+use crate::{
+    layers::{
+        embedding::{
+            DInputOfEmbeddingLayer, Embedding, InputOfEmbeddingLayer, OutputOfEmbeddingLayer,
+        },
+        layer::Layer,
+    },
+    matrix::{
+        matrix_one_dim::MatrixOneDim, matrix_three_dim::MatrixThreeDim,
+        matrix_two_dim::MatrixTwoDim,
+    },
+};
+
+use super::layer::LayerBase;
+
+pub(crate) struct TimeEmbeddingLayer<M3, M2, M1> {
+    embedding: Embedding<M2, M1>,
+    params: ParamsOfEmbeddingLayer<M2>,
+    grads: ParamsOfEmbeddingLayer<M2>,
+    // (バッチサイズ, 時刻)
+    dim: Option<(usize, usize)>,
+    ph: PhantomData<M3>,
+}
+
+// w: (語彙数, 次元)
+#[derive(Clone)]
+pub struct ParamsOfEmbeddingLayer<M2> {
+    pub(crate) w: M2,
+}
+
+impl<M2> Add for ParamsOfEmbeddingLayer<M2>
+where
+    M2: Add<Output = M2>,
+{
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        ParamsOfEmbeddingLayer { w: self.w + rhs.w }
+    }
+}
+
+impl<M2> Mul<f32> for ParamsOfEmbeddingLayer<M2>
+where
+    M2: Mul<f32, Output = M2>,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: f32) -> Self::Output {
+        ParamsOfEmbeddingLayer { w: self.w * rhs }
+    }
+}
+
+impl<M3, M2, M1> LayerBase for TimeEmbeddingLayer<M3, M2, M1>
+where
+    M3: MatrixThreeDim<M2, M1>,
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    type Params = ParamsOfEmbeddingLayer<M2>;
+
+    fn new(params: Self::Params) -> Self {
+        let grads = ParamsOfEmbeddingLayer {
+            w: M2::zeros_like(&params.w),
+        };
+        Self {
+            embedding: Embedding::new(),
+            params,
+            grads,
+            dim: None,
+            ph: PhantomData,
+        }
+    }
+
+    fn params_and_grads(&mut self) -> (&mut Self::Params, &Self::Params) {
+        (&mut self.params, &self.grads)
+    }
+}
+
+impl<M3, M2, M1> TimeEmbeddingLayer<M3, M2, M1>
+where
+    M3: MatrixThreeDim<M2, M1>,
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    // ids[n][t] はバッチの n 番目の系列の時刻 t の単語 ID
+    pub(crate) fn forward(&mut self, ids: &[Vec<usize>]) -> M3 {
+        let batch_size = ids.len();
+        assert!(batch_size > 0);
+        let time_size = ids[0].len();
+        assert!(ids.iter().all(|row| row.len() == time_size));
+        self.dim = Some((batch_size, time_size));
+
+        // (バッチサイズ * 時刻, 次元) の行列としてまとめて取り出す
+        let out = self
+            .embedding
+            .forward(InputOfEmbeddingLayer {
+                idx: ids.concat(),
+                w: self.params.w.clone(),
+            })
+            .into_value();
+        M3::from_matrix(out, batch_size, time_size)
+    }
+
+    pub(crate) fn backward(&mut self, dout: M3) {
+        assert!(self.dim.is_some());
+        let DInputOfEmbeddingLayer { dw } = self
+            .embedding
+            .backward(OutputOfEmbeddingLayer::from(dout.into_matrix()));
+        self.grads.w = dw;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Array1, Array2, Array3};
+
+    use super::*;
+
+    #[test]
+    fn test_time_embedding_layer() {
+        let mut layer = TimeEmbeddingLayer::<Array3<f32>, Array2<f32>, Array1<f32>>::new(
+            ParamsOfEmbeddingLayer {
+                w: array![[1., 2.], [3., 4.], [5., 6.]],
+            },
+        );
+
+        // test forward
+        let out = layer.forward(&[vec![0, 2], vec![1, 0]]);
+        assert_eq!(out, array![[[1., 2.], [5., 6.]], [[3., 4.], [1., 2.]]]);
+
+        // test backward
+        layer.backward(array![[[1., 1.], [2., 2.]], [[3., 3.], [4., 4.]]]);
+        let (_, grads) = layer.params_and_grads();
+        assert_eq!(grads.w, array![[5., 5.], [3., 3.], [2., 2.]]);
+    }
+}
@@ -0,0 +1,208 @@
+/*
+    T 時刻分の RNN・LSTM・GRU をまとめたレイヤ
+    最後の状態を保持し、次のミニバッチの最初の時刻に引き継ぐ (stateful)
+    ミニバッチごとに独立に扱う場合は forward の前に reset_state を呼ぶ
+
+    逆伝播はミニバッチ内の T 時刻分だけで打ち切る (Truncated BPTT)
+
+    １時刻分の計算は RecurrentCell を実装したレイヤ (rnn.rs, lstm.rs, gru.rs) に任せる
+*/
+
+use std::{
+    marker::PhantomData,
+    ops::{Add, Mul},
+};
+
+use crate::{
+    layers::layer::Layer,
+    matrix::{
+        matrix_one_dim::MatrixOneDim, matrix_three_dim::MatrixThreeDim,
+        matrix_two_dim::MatrixTwoDim,
+    },
+};
+
+use super::layer::{LayerBase, TimeIntermediateLayer};
+
+// 隠れ状態を次の時刻に渡す１時刻分のレイヤ
+pub(crate) trait RecurrentCell<M2, M1>: Layer<M2, M1>
+where
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    // 時刻をまたいで受け渡す状態（LSTM では隠れ状態と記憶セルの組）
+    type State;
+    // wx, wh の列数が隠れ状態の次元の何倍か（ゲートごとの重みを列方向に連結している）
+    const NUMBER_OF_GATES: usize;
+
+    fn zero_state(batch_size: usize, hidden_size: usize) -> Self::State;
+    // 状態のうち、各時刻の出力となる隠れ状態
+    fn hidden(state: &Self::State) -> &M2;
+    // 次の時刻から流れてきた状態の勾配に、出力の勾配 dh を足し合わせる
+    fn add_hidden_grad(dstate: Self::State, dh: M2) -> Self::State;
+
+    fn step_forward(
+        &mut self,
+        x: M2,
+        state: Self::State,
+        params: &ParamsOfRecurrentLayer<M2, M1>,
+    ) -> Self::State;
+    // (∂L/∂x, 前の時刻の状態の勾配, パラメータの勾配) を返す
+    fn step_backward(
+        &self,
+        dstate: Self::State,
+    ) -> (M2, Self::State, ParamsOfRecurrentLayer<M2, M1>);
+}
+
+pub(crate) struct TimeRecurrentLayer<M3, M2, M1, C>
+where
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+    C: RecurrentCell<M2, M1>,
+{
+    cells: Vec<C>,
+    params: ParamsOfRecurrentLayer<M2, M1>,
+    grads: ParamsOfRecurrentLayer<M2, M1>,
+    // 直前のミニバッチの最後の状態
+    state: Option<C::State>,
+    ph: PhantomData<M3>,
+}
+
+// wx: (入力の次元, NUMBER_OF_GATES * 隠れ状態の次元)
+// wh: (隠れ状態の次元, NUMBER_OF_GATES * 隠れ状態の次元)
+#[derive(Clone)]
+pub struct ParamsOfRecurrentLayer<M2, M1> {
+    pub(crate) wx: M2,
+    pub(crate) wh: M2,
+    pub(crate) b: M1,
+}
+
+impl<M2, M1> Add for ParamsOfRecurrentLayer<M2, M1>
+where
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        ParamsOfRecurrentLayer {
+            wx: self.wx + rhs.wx,
+            wh: self.wh + rhs.wh,
+            b: self.b + rhs.b,
+        }
+    }
+}
+
+impl<M2, M1> Mul<f32> for ParamsOfRecurrentLayer<M2, M1>
+where
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: f32) -> Self::Output {
+        ParamsOfRecurrentLayer {
+            wx: self.wx * rhs,
+            wh: self.wh * rhs,
+            b: self.b * rhs,
+        }
+    }
+}
+
+impl<M2, M1> ParamsOfRecurrentLayer<M2, M1>
+where
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    fn zeros_like(&self) -> Self {
+        ParamsOfRecurrentLayer {
+            wx: M2::zeros_like(&self.wx),
+            wh: M2::zeros_like(&self.wh),
+            b: M1::zeros(self.b.len()),
+        }
+    }
+}
+
+impl<M3, M2, M1, C> LayerBase for TimeRecurrentLayer<M3, M2, M1, C>
+where
+    M3: MatrixThreeDim<M2, M1>,
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+    C: RecurrentCell<M2, M1>,
+{
+    type Params = ParamsOfRecurrentLayer<M2, M1>;
+
+    fn new(params: Self::Params) -> Self {
+        let (hidden_size, width) = params.wh.dim();
+        assert_eq!(width, C::NUMBER_OF_GATES * hidden_size);
+        let grads = params.zeros_like();
+        Self {
+            cells: vec![],
+            params,
+            grads,
+            state: None,
+            ph: PhantomData,
+        }
+    }
+
+    fn params_and_grads(&mut self) -> (&mut Self::Params, &Self::Params) {
+        (&mut self.params, &self.grads)
+    }
+}
+
+impl<M3, M2, M1, C> TimeIntermediateLayer<M3, M2, M1> for TimeRecurrentLayer<M3, M2, M1, C>
+where
+    M3: MatrixThreeDim<M2, M1>,
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+    C: RecurrentCell<M2, M1>,
+{
+    fn forward(&mut self, xs: M3) -> M3 {
+        let (batch_size, time_size, _) = xs.dim();
+        let hidden_size = self.params.wh.dim().0;
+
+        // 引き継いだ状態がなければ０から始める
+        let mut state = self
+            .state
+            .take()
+            .unwrap_or_else(|| C::zero_state(batch_size, hidden_size));
+        assert_eq!(C::hidden(&state).dim(), (batch_size, hidden_size));
+
+        self.cells = Vec::with_capacity(time_size);
+        let mut hs = Vec::with_capacity(time_size);
+        for x in xs.into_time_slices() {
+            let mut cell = C::new();
+            state = cell.step_forward(x, state, &self.params);
+            hs.push(C::hidden(&state).clone());
+            self.cells.push(cell);
+        }
+
+        self.state = Some(state);
+        M3::from_time_slices(hs)
+    }
+
+    fn backward(&mut self, dout: M3) -> M3 {
+        let dhs = dout.into_time_slices();
+        assert_eq!(dhs.len(), self.cells.len());
+
+        // 最後の時刻より先からの勾配は流さない
+        let (batch_size, hidden_size) = dhs[0].dim();
+        let mut dstate = C::zero_state(batch_size, hidden_size);
+        let mut grads = self.params.zeros_like();
+        let mut dxs = Vec::with_capacity(dhs.len());
+        for (cell, dh) in self.cells.iter().zip(dhs).rev() {
+            // 隠れ状態は出力と次の時刻の両方に分岐しているので、勾配を足し合わせる
+            let (dx, dstate_prev, dparams) = cell.step_backward(C::add_hidden_grad(dstate, dh));
+            dstate = dstate_prev;
+            grads = grads + dparams;
+            dxs.push(dx);
+        }
+        dxs.reverse();
+
+        self.grads = grads;
+        M3::from_time_slices(dxs)
+    }
+
+    fn reset_state(&mut self) {
+        self.state = None;
+    }
+}
@@ -0,0 +1,205 @@
+/*
+    全時刻の softmax と交差エントロピー誤差をまとめて計算するレイヤ
+    X: (バッチサイズ, 時刻, 語彙数), t[n][τ]: 正解の ID
+    L = (1 / |対象の時刻|) Σ_{(n, τ): 対象の時刻} (- ln(y[n, τ, t[n][τ]]))
+
+    ∂L/∂x[n, τ, k] = (y[n, τ, k] - δ_{k, t[n][τ]}) / |対象の時刻|  (対象外の時刻は０)
+
+    正解が ignore_label の時刻 (パディングなど) は対象から除く
+*/
+
+use std::marker::PhantomData;
+
+use crate::{
+    layers::softmax_cross_entropy::SoftmaxCrossEntropy,
+    matrix::{
+        matrix_one_dim::MatrixOneDim, matrix_three_dim::MatrixThreeDim,
+        matrix_two_dim::MatrixTwoDim,
+    },
+};
+
+use super::layer::{LayerBase, TimeLossLayer};
+
+const TINY_DELTA: f32 = 1e-10;
+
+pub(crate) struct TimeSoftmaxWithLossLayer<M3, M2, M1> {
+    params: ParamsOfTimeSoftmaxWithLossLayer,
+    grads: ParamsOfTimeSoftmaxWithLossLayer,
+    // (バッチサイズ * 時刻, 語彙数) の softmax の出力
+    y: Option<M2>,
+    // y の各行の正解 (対象外の時刻は None)
+    labels: Option<Vec<Option<usize>>>,
+    // (バッチサイズ, 時刻)
+    dim: Option<(usize, usize)>,
+    ph: PhantomData<(M3, M1)>,
+}
+
+#[derive(Clone)]
+pub(crate) struct ParamsOfTimeSoftmaxWithLossLayer {
+    pub(crate) ignore_label: Option<usize>,
+}
+
+impl<M3, M2, M1> LayerBase for TimeSoftmaxWithLossLayer<M3, M2, M1>
+where
+    M3: MatrixThreeDim<M2, M1>,
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    type Params = ParamsOfTimeSoftmaxWithLossLayer;
+
+    fn new(params: Self::Params) -> Self {
+        Self {
+            grads: params.clone(),
+            params,
+            y: None,
+            labels: None,
+            dim: None,
+            ph: PhantomData,
+        }
+    }
+
+    fn params_and_grads(&mut self) -> (&mut Self::Params, &Self::Params) {
+        (&mut self.params, &self.grads)
+    }
+}
+
+impl<M3, M2, M1> TimeSoftmaxWithLossLayer<M3, M2, M1>
+where
+    M3: MatrixThreeDim<M2, M1>,
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    fn number_of_targets(&self) -> usize {
+        self.labels
+            .as_ref()
+            .unwrap()
+            .iter()
+            .filter(|label| label.is_some())
+            .count()
+    }
+}
+
+impl<M3, M2, M1> TimeLossLayer<M3, M2, M1> for TimeSoftmaxWithLossLayer<M3, M2, M1>
+where
+    M3: MatrixThreeDim<M2, M1>,
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    fn forward(&mut self, xs: M3, labels: &[Vec<usize>]) -> f32 {
+        let (batch_size, time_size, _) = xs.dim();
+        assert_eq!(labels.len(), batch_size);
+        assert!(labels.iter().all(|row| row.len() == time_size));
+        self.dim = Some((batch_size, time_size));
+
+        let ignore_label = self.params.ignore_label;
+        let labels = labels
+            .concat()
+            .into_iter()
+            .map(|label| Some(label).filter(|&label| Some(label) != ignore_label))
+            .collect::<Vec<_>>();
+        let y = SoftmaxCrossEntropy::softmax(xs.into_matrix());
+
+        let loss = y
+            .clone()
+            .indexed_mapv_into(|(n, k), y| {
+                if labels[n] == Some(k) {
+                    -(y.max(TINY_DELTA)).ln()
+                } else {
+                    0.
+                }
+            })
+            .sum();
+
+        self.y = Some(y);
+        self.labels = Some(labels);
+        let number_of_targets = self.number_of_targets();
+        if number_of_targets == 0 {
+            0.
+        } else {
+            loss / number_of_targets as f32
+        }
+    }
+
+    fn backward(&mut self, dout: f32) -> M3 {
+        assert!(self.y.is_some());
+        assert!(self.labels.is_some());
+        let (batch_size, time_size) = self.dim.unwrap();
+        let number_of_targets = self.number_of_targets().max(1) as f32;
+        let labels = self.labels.as_ref().unwrap();
+
+        let dx = self
+            .y
+            .clone()
+            .unwrap()
+            .indexed_mapv_into(|(n, k), y| match labels[n] {
+                Some(label) => {
+                    let t = if label == k { 1. } else { 0. };
+                    (y - t) * dout / number_of_targets
+                }
+                None => 0.,
+            });
+        M3::from_matrix(dx, batch_size, time_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    use ndarray::{array, Array1, Array2, Array3};
+
+    use crate::layers::gradient_check::{assert_gradient_eq, numerical_gradient};
+
+    use super::*;
+
+    fn xs() -> Array3<f32> {
+        array![
+            [[1., 2., 0.5], [0., -1., 3.]],
+            [[2., 2., 2.], [-0.5, 1., 0.]]
+        ]
+    }
+
+    fn layer(
+        ignore_label: Option<usize>,
+    ) -> TimeSoftmaxWithLossLayer<Array3<f32>, Array2<f32>, Array1<f32>> {
+        TimeSoftmaxWithLossLayer::new(ParamsOfTimeSoftmaxWithLossLayer { ignore_label })
+    }
+
+    // softmax(x)[label] の負の対数
+    fn cross_entropy(x: [f32; 3], label: usize) -> f32 {
+        let sum: f32 = x.iter().map(|x| x.exp()).sum();
+        -(x[label].exp() / sum).ln()
+    }
+
+    #[test]
+    fn test_time_softmax_with_loss_forward() {
+        let labels = [vec![1, 2], vec![0, 1]];
+        let expected = (cross_entropy([1., 2., 0.5], 1)
+            + cross_entropy([0., -1., 3.], 2)
+            + cross_entropy([2., 2., 2.], 0)
+            + cross_entropy([-0.5, 1., 0.], 1))
+            / 4.;
+        assert_abs_diff_eq!(layer(None).forward(xs(), &labels), expected, epsilon = 1e-6);
+
+        // 正解が ignore_label の時刻は平均から除く
+        let labels = [vec![1, 2], vec![2, 1]];
+        let expected = (cross_entropy([1., 2., 0.5], 1) + cross_entropy([-0.5, 1., 0.], 1)) / 2.;
+        assert_abs_diff_eq!(
+            layer(Some(2)).forward(xs(), &labels),
+            expected,
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    fn test_time_softmax_with_loss_backward() {
+        let labels = [vec![1, 0], vec![0, 2]];
+        for ignore_label in [None, Some(0)] {
+            let mut loss_layer = layer(ignore_label);
+            let _ = loss_layer.forward(xs(), &labels);
+            let dxs = loss_layer.backward(1.);
+
+            let expected = numerical_gradient(&xs(), |xs| layer(ignore_label).forward(xs, &labels));
+            assert_gradient_eq(&dxs, &expected);
+        }
+    }
+}
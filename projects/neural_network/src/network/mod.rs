@@ -1,4 +1,10 @@
 pub(crate) mod layers;
+pub mod generation;
+pub mod language_model;
 pub mod network;
 
+pub mod rnn_lm;
 pub mod simple_network;
+
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
@@ -0,0 +1,327 @@
+/*
+    LSTM による言語モデル
+    単語 ID -> TimeEmbedding -> TimeLstm -> TimeAffine -> TimeSoftmaxWithLoss
+    各時刻で次の単語の確率分布を予測する
+
+    LSTM の隠れ状態はミニバッチをまたいで引き継ぐ (Truncated BPTT)
+    with_cell で LSTM の代わりに GRU や単純な RNN を使うこともできる
+*/
+
+use crate::{
+    layers::{gru::Gru, lstm::Lstm, rnn::Rnn},
+    matrix::{
+        matrix_one_dim::MatrixOneDim, matrix_three_dim::MatrixThreeDim,
+        matrix_two_dim::MatrixTwoDim,
+    },
+    optimizer::optimizer::Optimizer,
+};
+
+use super::{
+    language_model::LanguageModel,
+    layers::{
+        affine::ParamsOfAffineLayer,
+        gru::TimeGruLayer,
+        layer::{LayerBase, TimeIntermediateLayer, TimeLossLayer},
+        lstm::TimeLstmLayer,
+        rnn::TimeRnnLayer,
+        time_affine::TimeAffineLayer,
+        time_embedding::{ParamsOfEmbeddingLayer, TimeEmbeddingLayer},
+        time_recurrent::{ParamsOfRecurrentLayer, RecurrentCell},
+        time_softmax_with_loss::{ParamsOfTimeSoftmaxWithLossLayer, TimeSoftmaxWithLossLayer},
+    },
+};
+
+// ハイパーパラメータ
+const MEAN_DISTR: f32 = 0.;
+const STD_DEV_EMBEDDING: f32 = 0.01;
+// 勾配クリッピングでゼロ除算を避けるための値
+const TINY_DELTA: f32 = 1e-6;
+
+// 中間層に使う再帰的なレイヤ
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecurrentCellKind {
+    Rnn,
+    Lstm,
+    Gru,
+}
+
+pub struct RnnLm<M3, M2, M1>
+where
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    embedding: TimeEmbeddingLayer<M3, M2, M1>,
+    recurrent: TimeRecurrent<M3, M2, M1>,
+    affine: TimeAffineLayer<M3, M2, M1>,
+    loss_layer: TimeSoftmaxWithLossLayer<M3, M2, M1>,
+}
+
+impl<M3, M2, M1> RnnLm<M3, M2, M1>
+where
+    M3: MatrixThreeDim<M2, M1>,
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    pub fn new(vocab_size: usize, wordvec_size: usize, hidden_size: usize) -> Self {
+        Self::with_cell(
+            vocab_size,
+            wordvec_size,
+            hidden_size,
+            RecurrentCellKind::Lstm,
+        )
+    }
+
+    pub fn with_cell(
+        vocab_size: usize,
+        wordvec_size: usize,
+        hidden_size: usize,
+        cell: RecurrentCellKind,
+    ) -> Self {
+        let embedding = TimeEmbeddingLayer::new(ParamsOfEmbeddingLayer {
+            w: M2::random_normal((vocab_size, wordvec_size), MEAN_DISTR, STD_DEV_EMBEDDING),
+        });
+        let recurrent = match cell {
+            RecurrentCellKind::Rnn => TimeRecurrent::Rnn(TimeRnnLayer::new(
+                Self::recurrent_params::<Rnn<M2, M1>>(wordvec_size, hidden_size),
+            )),
+            RecurrentCellKind::Lstm => TimeRecurrent::Lstm(TimeLstmLayer::new(
+                Self::recurrent_params::<Lstm<M2, M1>>(wordvec_size, hidden_size),
+            )),
+            RecurrentCellKind::Gru => TimeRecurrent::Gru(TimeGruLayer::new(
+                Self::recurrent_params::<Gru<M2, M1>>(wordvec_size, hidden_size),
+            )),
+        };
+        let affine = TimeAffineLayer::new(ParamsOfAffineLayer {
+            w: M2::random_normal((hidden_size, vocab_size), MEAN_DISTR, xavier(hidden_size)),
+            b: M1::zeros(vocab_size),
+        });
+
+        Self {
+            embedding,
+            recurrent,
+            affine,
+            loss_layer: TimeSoftmaxWithLossLayer::new(ParamsOfTimeSoftmaxWithLossLayer {
+                ignore_label: None,
+            }),
+        }
+    }
+
+    fn recurrent_params<C: RecurrentCell<M2, M1>>(
+        wordvec_size: usize,
+        hidden_size: usize,
+    ) -> ParamsOfRecurrentLayer<M2, M1> {
+        let width = C::NUMBER_OF_GATES * hidden_size;
+        ParamsOfRecurrentLayer {
+            wx: M2::random_normal((wordvec_size, width), MEAN_DISTR, xavier(wordvec_size)),
+            wh: M2::random_normal((hidden_size, width), MEAN_DISTR, xavier(hidden_size)),
+            b: M1::zeros(width),
+        }
+    }
+
+    // 全パラメータの勾配を１つのベクトルとみなしたときのノルム
+    fn grads_norm(&mut self) -> f32 {
+        let squared = |m: &M2| m.clone().mapv_into(|x| x * x).sum();
+        let squared_1d = |m: &M1| m.clone().mapv_into(|x| x * x).sum();
+
+        let (_, grads) = self.embedding.params_and_grads();
+        let mut sum = squared(&grads.w);
+        let (_, grads) = self.recurrent.params_and_grads();
+        sum += squared(&grads.wx) + squared(&grads.wh) + squared_1d(&grads.b);
+        let (_, grads) = self.affine.params_and_grads();
+        sum += squared(&grads.w) + squared_1d(&grads.b);
+        sum.sqrt()
+    }
+}
+
+// 再帰的なレイヤと Affine の重みは Xavier の初期値 (標準偏差 1 / √(入力の次元))
+fn xavier(n: usize) -> f32 {
+    1. / (n as f32).sqrt()
+}
+
+// RecurrentCellKind で選んだレイヤに振り分ける
+enum TimeRecurrent<M3, M2, M1>
+where
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    Rnn(TimeRnnLayer<M3, M2, M1>),
+    Lstm(TimeLstmLayer<M3, M2, M1>),
+    Gru(TimeGruLayer<M3, M2, M1>),
+}
+
+impl<M3, M2, M1> TimeRecurrent<M3, M2, M1>
+where
+    M3: MatrixThreeDim<M2, M1>,
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    fn forward(&mut self, xs: M3) -> M3 {
+        match self {
+            Self::Rnn(layer) => layer.forward(xs),
+            Self::Lstm(layer) => layer.forward(xs),
+            Self::Gru(layer) => layer.forward(xs),
+        }
+    }
+
+    fn backward(&mut self, dout: M3) -> M3 {
+        match self {
+            Self::Rnn(layer) => layer.backward(dout),
+            Self::Lstm(layer) => layer.backward(dout),
+            Self::Gru(layer) => layer.backward(dout),
+        }
+    }
+
+    fn reset_state(&mut self) {
+        match self {
+            Self::Rnn(layer) => layer.reset_state(),
+            Self::Lstm(layer) => layer.reset_state(),
+            Self::Gru(layer) => layer.reset_state(),
+        }
+    }
+
+    fn params_and_grads(
+        &mut self,
+    ) -> (
+        &mut ParamsOfRecurrentLayer<M2, M1>,
+        &ParamsOfRecurrentLayer<M2, M1>,
+    ) {
+        match self {
+            Self::Rnn(layer) => layer.params_and_grads(),
+            Self::Lstm(layer) => layer.params_and_grads(),
+            Self::Gru(layer) => layer.params_and_grads(),
+        }
+    }
+}
+
+impl<M3, M2, M1> LanguageModel<M3, M2, M1> for RnnLm<M3, M2, M1>
+where
+    M3: MatrixThreeDim<M2, M1>,
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    fn predict(&mut self, ids: &[Vec<usize>]) -> M3 {
+        let xs = self.embedding.forward(ids);
+        let hs = self.recurrent.forward(xs);
+        self.affine.forward(hs)
+    }
+
+    fn forward(&mut self, ids: &[Vec<usize>], labels: &[Vec<usize>]) -> f32 {
+        let score = self.predict(ids);
+        self.loss_layer.forward(score, labels)
+    }
+
+    fn backward(&mut self, dout: f32) {
+        let dout = self.loss_layer.backward(dout);
+        let dout = self.affine.backward(dout);
+        let dout = self.recurrent.backward(dout);
+        self.embedding.backward(dout);
+    }
+
+    fn update<T: Optimizer>(&mut self, optimizer: &T, max_grad: Option<f32>) {
+        // 勾配クリッピング
+        let rate = match max_grad {
+            Some(max_grad) => (max_grad / (self.grads_norm() + TINY_DELTA)).min(1.),
+            None => 1.,
+        };
+
+        let (params, grads) = self.embedding.params_and_grads();
+        optimizer.update(params, &(grads.clone() * rate));
+        let (params, grads) = self.recurrent.params_and_grads();
+        optimizer.update(params, &(grads.clone() * rate));
+        let (params, grads) = self.affine.params_and_grads();
+        optimizer.update(params, &(grads.clone() * rate));
+    }
+
+    fn reset_state(&mut self) {
+        self.recurrent.reset_state();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{Array1, Array2, Array3};
+
+    use crate::optimizer::imp::sgd::{learning_rate::LearningRate, SGD};
+
+    use super::*;
+
+    type ArrayRnnLm = RnnLm<Array3<f32>, Array2<f32>, Array1<f32>>;
+
+    // 0 1 2 3 0 1 2 3 ... の次の単語を予測する
+    fn ids_and_labels() -> (Vec<Vec<usize>>, Vec<Vec<usize>>) {
+        let ids = vec![vec![0, 1, 2, 3, 0], vec![2, 3, 0, 1, 2]];
+        let labels = ids
+            .iter()
+            .map(|row| row.iter().map(|id| (id + 1) % 4).collect())
+            .collect();
+        (ids, labels)
+    }
+
+    #[test]
+    fn test_rnn_lm_predict() {
+        let (ids, _) = ids_and_labels();
+        for cell in [
+            RecurrentCellKind::Rnn,
+            RecurrentCellKind::Lstm,
+            RecurrentCellKind::Gru,
+        ] {
+            let mut rnn_lm = ArrayRnnLm::with_cell(4, 3, 5, cell);
+            assert_eq!(rnn_lm.predict(&ids).dim(), (2, 5, 4));
+        }
+    }
+
+    #[test]
+    fn test_rnn_lm_learns_sequence() {
+        for cell in [
+            RecurrentCellKind::Rnn,
+            RecurrentCellKind::Lstm,
+            RecurrentCellKind::Gru,
+        ] {
+            let mut rnn_lm = ArrayRnnLm::with_cell(4, 8, 16, cell);
+            let optimizer = SGD::new(LearningRate::new(1.));
+            let (ids, labels) = ids_and_labels();
+
+            let initial_loss = rnn_lm.forward(&ids, &labels);
+            let mut loss = initial_loss;
+            for _ in 0..300 {
+                rnn_lm.reset_state();
+                loss = rnn_lm.forward(&ids, &labels);
+                rnn_lm.backward(1.);
+                rnn_lm.update(&optimizer, Some(5.));
+            }
+            assert!(loss < initial_loss / 4.);
+        }
+    }
+
+    #[test]
+    fn test_rnn_lm_clips_grads() {
+        let mut rnn_lm = ArrayRnnLm::new(4, 3, 5);
+        let optimizer = SGD::new(LearningRate::new(1.));
+        let (ids, labels) = ids_and_labels();
+
+        let _ = rnn_lm.forward(&ids, &labels);
+        rnn_lm.backward(1.);
+        let (params, _) = rnn_lm.recurrent.params_and_grads();
+        let before = params.clone();
+
+        // 学習率が１なので、パラメータ全体の変化量のノルムは max_grad 以下になる
+        let max_grad = 1e-3;
+        assert!(rnn_lm.grads_norm() > max_grad);
+        rnn_lm.update(&optimizer, Some(max_grad));
+        let (params, _) = rnn_lm.recurrent.params_and_grads();
+        let diff = (params.wx.clone() - before.wx).mapv_into(|x| x * x).sum()
+            + (params.wh.clone() - before.wh).mapv_into(|x| x * x).sum()
+            + (params.b.clone() - before.b).mapv_into(|x| x * x).sum();
+        assert!(diff.sqrt() <= max_grad + 1e-6);
+
+        // クリッピングしない場合は勾配をそのまま使う
+        let mut rnn_lm = ArrayRnnLm::new(4, 3, 5);
+        let _ = rnn_lm.forward(&ids, &labels);
+        rnn_lm.backward(1.);
+        let (params, _) = rnn_lm.affine.params_and_grads();
+        let before = params.clone();
+        rnn_lm.update(&optimizer, None);
+        let (params, grads) = rnn_lm.affine.params_and_grads();
+        assert_eq!(params.w, before.w - &grads.w);
+    }
+}
@@ -0,0 +1,38 @@
+/*
+    言語モデルを使う処理のテスト用のモデル
+    他のクレートのテストからは test-support フィーチャを有効にして使う
+*/
+
+use ndarray::{Array1, Array2, Array3};
+
+use crate::optimizer::optimizer::Optimizer;
+
+use super::language_model::LanguageModel;
+
+// 単語 id の次は必ず (id + 1) % vocab_size を予測する言語モデル
+pub struct CyclicLm {
+    pub vocab_size: usize,
+}
+
+impl LanguageModel<Array3<f32>, Array2<f32>, Array1<f32>> for CyclicLm {
+    fn predict(&mut self, ids: &[Vec<usize>]) -> Array3<f32> {
+        let (batch_size, time_size) = (ids.len(), ids[0].len());
+        Array3::from_shape_fn((batch_size, time_size, self.vocab_size), |(n, t, k)| {
+            if k == (ids[n][t] + 1) % self.vocab_size {
+                10.
+            } else {
+                0.
+            }
+        })
+    }
+
+    fn forward(&mut self, _ids: &[Vec<usize>], _labels: &[Vec<usize>]) -> f32 {
+        0.
+    }
+
+    fn backward(&mut self, _dout: f32) {}
+
+    fn update<T: Optimizer>(&mut self, _optimizer: &T, _max_grad: Option<f32>) {}
+
+    fn reset_state(&mut self) {}
+}
@@ -0,0 +1,239 @@
+/*
+    言語モデルの学習
+    評価には正解率の代わりにパープレキシティ exp(損失の平均) を用いる
+    (次の単語の候補を平均して何個にまで絞り込めているかを表し、小さいほど良い)
+*/
+
+use anyhow::Result;
+use std::{marker::PhantomData, time::Instant};
+
+use crate::{
+    dataset::dataset::{TimeSeriesDataset, TimeSeriesMiniBatch},
+    matrix::{
+        matrix_one_dim::MatrixOneDim, matrix_three_dim::MatrixThreeDim,
+        matrix_two_dim::MatrixTwoDim,
+    },
+    network::language_model::LanguageModel,
+    optimizer::optimizer::Optimizer,
+    trainer::plot,
+};
+
+pub struct RnnLmTrainer<Net, Opt, M3, M2, M1>
+where
+    Net: LanguageModel<M3, M2, M1>,
+    Opt: Optimizer,
+    M3: MatrixThreeDim<M2, M1>,
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    network: Net,
+    optimizer: Opt,
+    ppl_list: Vec<f32>,
+    eval_ppl_list: Vec<f32>,
+    eval_interval: Option<usize>,
+    phantom: PhantomData<(M3, M2, M1)>,
+}
+
+impl<Net, Opt, M3, M2, M1> RnnLmTrainer<Net, Opt, M3, M2, M1>
+where
+    Net: LanguageModel<M3, M2, M1>,
+    Opt: Optimizer,
+    M3: MatrixThreeDim<M2, M1>,
+    M2: MatrixTwoDim<M1>,
+    M1: MatrixOneDim,
+{
+    pub fn new(network: Net, optimizer: Opt) -> Self {
+        Self {
+            network,
+            optimizer,
+            ppl_list: Vec::new(),
+            eval_ppl_list: Vec::new(),
+            eval_interval: None,
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn fit<D: TimeSeriesDataset>(
+        &mut self,
+        dataset: &mut D,
+        max_epoch: usize,
+        max_grad: Option<f32>,
+        eval_interval: usize,
+    ) {
+        self.eval_interval = Some(eval_interval);
+
+        // 損失の合計値の初期化
+        let mut total_loss = 0.;
+        let mut loss_count = 0;
+
+        // １エポック当たりのイテレーション数
+        let max_iter = dataset.len();
+
+        // 実行時間の計測開始
+        let start_time = Instant::now();
+
+        // 学習の実行
+        for epoch in 0..max_epoch {
+            // コーパスの先頭に戻るので、隠れ状態も捨てる
+            dataset.reset_cursor();
+            self.network.reset_state();
+
+            // ミニバッチを取得して学習を実行
+            for (iters, TimeSeriesMiniBatch { inputs, labels }) in dataset.enumerate() {
+                let loss = self.network.forward(&inputs, &labels);
+                self.network.backward(1.);
+                self.network.update(&self.optimizer, max_grad);
+
+                // 損失の合計値の更新
+                total_loss += loss;
+                loss_count += 1;
+
+                // 評価
+                if loss_count == eval_interval {
+                    // パープレキシティの計算
+                    let ppl = (total_loss / loss_count as f32).exp();
+                    // 実行時間の取得
+                    let elapsed_time = start_time.elapsed().as_secs_f32();
+
+                    // 時間、イテレーション、パープレキシティの表示
+                    println!(
+                        "| epoch {:5} | iter {:5} / {:5} | time {:.5} [s] | perplexity {:.5}",
+                        epoch + 1,
+                        iters,
+                        max_iter,
+                        elapsed_time,
+                        ppl
+                    );
+
+                    // パープレキシティの履歴の更新
+                    self.ppl_list.push(ppl);
+
+                    // 損失の合計値の初期化
+                    total_loss = 0.;
+                    loss_count = 0;
+                }
+            }
+
+            // テストデータでのパープレキシティの計算
+            let test_data = dataset.test_data();
+            if !test_data.is_empty() {
+                let ppl = self.perplexity(&test_data);
+                self.eval_ppl_list.push(ppl);
+                println!("| epoch {:5} | test perplexity {:5.5}", epoch + 1, ppl);
+            }
+        }
+    }
+
+    // mini_batches を順に流したときのパープレキシティ
+    // 学習中の隠れ状態を汚さないよう、前後で状態を捨てる
+    pub fn perplexity(&mut self, mini_batches: &[TimeSeriesMiniBatch]) -> f32 {
+        assert!(!mini_batches.is_empty());
+        self.network.reset_state();
+        let total_loss: f32 = mini_batches
+            .iter()
+            .map(|TimeSeriesMiniBatch { inputs, labels }| self.network.forward(inputs, labels))
+            .sum();
+        self.network.reset_state();
+        (total_loss / mini_batches.len() as f32).exp()
+    }
+
+    pub fn network(&mut self) -> &mut Net {
+        &mut self.network
+    }
+
+    pub fn into_network(self) -> Net {
+        self.network
+    }
+
+    pub fn plot_perplexity(&self, out_path: &'static str) -> Result<()> {
+        plot(
+            &self.ppl_list,
+            out_path,
+            &format!("iteration (x{:?})", self.eval_interval.unwrap()),
+            "perplexity",
+            "Perplexity",
+        )
+    }
+
+    pub fn plot_test_perplexity(&self, out_path: &'static str) -> Result<()> {
+        plot(
+            &self.eval_ppl_list,
+            out_path,
+            "epoch",
+            "perplexity",
+            "Test Perplexity",
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{Array1, Array2, Array3};
+
+    use crate::{
+        network::rnn_lm::RnnLm,
+        optimizer::imp::sgd::{learning_rate::LearningRate, SGD},
+    };
+
+    use super::*;
+
+    // 0 1 2 0 1 2 ... を time_size 時刻ずつ流すデータセット
+    struct CyclicDataset {
+        cursor: usize,
+        len: usize,
+    }
+
+    impl CyclicDataset {
+        fn mini_batch(offset: usize) -> TimeSeriesMiniBatch {
+            let inputs = vec![(offset..offset + 4).map(|i| i % 3).collect::<Vec<_>>()];
+            let labels = vec![inputs[0].iter().map(|id| (id + 1) % 3).collect()];
+            TimeSeriesMiniBatch { inputs, labels }
+        }
+    }
+
+    impl Iterator for CyclicDataset {
+        type Item = TimeSeriesMiniBatch;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            if self.cursor == self.len {
+                None
+            } else {
+                self.cursor += 1;
+                Some(Self::mini_batch(4 * self.cursor))
+            }
+        }
+    }
+
+    impl ExactSizeIterator for CyclicDataset {
+        fn len(&self) -> usize {
+            self.len
+        }
+    }
+
+    impl TimeSeriesDataset for CyclicDataset {
+        fn reset_cursor(&mut self) {
+            self.cursor = 0;
+        }
+
+        fn test_data(&self) -> Vec<TimeSeriesMiniBatch> {
+            vec![Self::mini_batch(0), Self::mini_batch(4)]
+        }
+    }
+
+    #[test]
+    fn test_rnn_lm_trainer() {
+        let network: RnnLm<Array3<f32>, Array2<f32>, Array1<f32>> = RnnLm::new(3, 8, 16);
+        let optimizer = SGD::new(LearningRate::new(1.));
+        let mut trainer = RnnLmTrainer::new(network, optimizer);
+        let mut dataset = CyclicDataset { cursor: 0, len: 10 };
+
+        // 学習前は一様分布に近いので、パープレキシティは語彙数程度
+        let initial_ppl = trainer.perplexity(&dataset.test_data());
+        assert!(initial_ppl > 2.);
+
+        trainer.fit(&mut dataset, 30, Some(5.), 5);
+        assert_eq!(trainer.ppl_list.len(), 60);
+        assert_eq!(trainer.eval_ppl_list.len(), 30);
+        assert!(*trainer.eval_ppl_list.last().unwrap() < 1.5);
+    }
+}
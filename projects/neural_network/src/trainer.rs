@@ -150,11 +150,11 @@ where
     }
 
     pub fn plot_accuracy(&self, out_path: &'static str) -> Result<()> {
-        Self::plot(&self.acc_list, out_path, "epoch", "accuracy", "Accuracy")
+        plot(&self.acc_list, out_path, "epoch", "accuracy", "Accuracy")
     }
 
     pub fn plot_loss(&self, out_path: &'static str) -> Result<()> {
-        Self::plot(
+        plot(
             &self.loss_list,
             out_path,
             &format!("iteration (x{:?})", self.eval_interval.unwrap()),
@@ -162,51 +162,51 @@ where
             "Loss",
         )
     }
+}
 
-    fn plot(
-        list: &Vec<f32>,
-        out_path: &str,
-        x_label: &str,
-        y_label: &str,
-        caption: &str,
-    ) -> Result<()> {
-        let max_x: f32 = list.len() as f32;
-        let max_y: f32 = *list.iter().max_by(|&a, &b| a.total_cmp(b)).unwrap();
-        let x = (0..max_x as usize).map(|x| x as f32);
-
-        // 背景の作成
-        let root = BitMapBackend::new(out_path, (640, 480)).into_drawing_area();
-        root.fill(&WHITE)?;
-
-        // グラフの描画範囲の設定
-        let mut chart = ChartBuilder::on(&root)
-            .x_label_area_size(70)
-            .y_label_area_size(80)
-            .margin(20)
-            .caption(caption, ("Arial", 40.0).into_font())
-            .build_cartesian_2d(0_f32..max_x, 0_f32..max_y)?;
-
-        // グラフのx軸、y軸の設定
-        chart
-            .configure_mesh()
-            .disable_mesh()
-            .x_desc(x_label)
-            .y_desc(y_label)
-            .x_label_formatter(&|x| format!("{:2.0}", x))
-            .y_label_formatter(&|x| format!("{:2.1}", x))
-            .axis_desc_style(FontDesc::new(FontFamily::SansSerif, 32., FontStyle::Normal))
-            .label_style(FontDesc::new(FontFamily::SansSerif, 24., FontStyle::Normal))
-            .draw()?;
-
-        // データの描画設定
-        chart.draw_series(LineSeries::new(
-            zip(x, list.iter()).map(|(x, y)| (x, *y)),
-            &BLUE,
-        ))?;
-
-        // グラフの描画
-        root.present().expect("Unable to write result to file, please make sure 'plotters-doc-data' dir exists under current dir");
-        println!("Result has been saved to {}", out_path);
-        Ok(())
-    }
+pub(crate) fn plot(
+    list: &[f32],
+    out_path: &str,
+    x_label: &str,
+    y_label: &str,
+    caption: &str,
+) -> Result<()> {
+    let max_x: f32 = list.len() as f32;
+    let max_y: f32 = *list.iter().max_by(|&a, &b| a.total_cmp(b)).unwrap();
+    let x = (0..max_x as usize).map(|x| x as f32);
+
+    // 背景の作成
+    let root = BitMapBackend::new(out_path, (640, 480)).into_drawing_area();
+    root.fill(&WHITE)?;
+
+    // グラフの描画範囲の設定
+    let mut chart = ChartBuilder::on(&root)
+        .x_label_area_size(70)
+        .y_label_area_size(80)
+        .margin(20)
+        .caption(caption, ("Arial", 40.0).into_font())
+        .build_cartesian_2d(0_f32..max_x, 0_f32..max_y)?;
+
+    // グラフのx軸、y軸の設定
+    chart
+        .configure_mesh()
+        .disable_mesh()
+        .x_desc(x_label)
+        .y_desc(y_label)
+        .x_label_formatter(&|x| format!("{:2.0}", x))
+        .y_label_formatter(&|x| format!("{:2.1}", x))
+        .axis_desc_style(FontDesc::new(FontFamily::SansSerif, 32., FontStyle::Normal))
+        .label_style(FontDesc::new(FontFamily::SansSerif, 24., FontStyle::Normal))
+        .draw()?;
+
+    // データの描画設定
+    chart.draw_series(LineSeries::new(
+        zip(x, list.iter()).map(|(x, y)| (x, *y)),
+        &BLUE,
+    ))?;
+
+    // グラフの描画
+    root.present().expect("Unable to write result to file, please make sure 'plotters-doc-data' dir exists under current dir");
+    println!("Result has been saved to {}", out_path);
+    Ok(())
 }